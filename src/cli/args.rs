@@ -1,12 +1,21 @@
-use std::path::PathBuf;
+use std::{num::NonZeroUsize, path::PathBuf};
 
-use clap::{ArgAction, Parser, Subcommand};
+use chrono::{DateTime, SecondsFormat, Utc};
+use clap::{ArgAction, CommandFactory, Parser, Subcommand, error::ErrorKind};
+
+use crate::{
+    output::{OutputFormat, human::HumanLayout},
+    walk::{
+        filter::{parse_size, parse_time_bound},
+        symlink::SymlinkPolicy,
+    },
+};
 
 #[derive(Debug, Parser)]
 #[command(
     name = "vacuum",
     about = "Enumerate artifacts and emit deterministic JSONL manifests",
-    long_about = "Enumerate artifacts and emit deterministic JSONL manifests.\n\nScan output is always JSONL on stdout. Use `vacuum --robot-triage`, `vacuum capabilities --json`, or `vacuum robot-docs guide` when an agent needs to discover the command contract without scanning a root."
+    long_about = "Enumerate artifacts and emit deterministic JSONL manifests.\n\nScan output is JSONL on stdout unless `--format` or `--human` is given. Use `vacuum --robot-triage`, `vacuum capabilities --json`, or `vacuum robot-docs guide` when an agent needs to discover the command contract without scanning a root."
 )]
 #[command(args_conflicts_with_subcommands = true)]
 #[command(disable_version_flag = true)]
@@ -18,6 +27,14 @@ pub struct Cli {
     #[arg(value_name = "ROOT")]
     pub roots: Vec<PathBuf>,
 
+    /// Read more roots from FILE (`-` for stdin), one per line or NUL-delimited
+    #[arg(long, value_name = "FILE")]
+    pub roots_from: Option<PathBuf>,
+
+    /// Record only the files listed in FILE (`-` for stdin), relative to the single ROOT
+    #[arg(long, value_name = "FILE", conflicts_with = "roots_from")]
+    pub files_from: Option<PathBuf>,
+
     /// Include only files matching this glob (repeatable)
     #[arg(long, action = ArgAction::Append, value_name = "GLOB")]
     pub include: Vec<String>,
@@ -26,14 +43,118 @@ pub struct Cli {
     #[arg(long, action = ArgAction::Append, value_name = "GLOB")]
     pub exclude: Vec<String>,
 
+    /// Include files whose relative path matches this regex (repeatable)
+    #[arg(long, action = ArgAction::Append, value_name = "REGEX")]
+    pub include_regex: Vec<String>,
+
+    /// Exclude files whose relative path matches this regex (repeatable)
+    #[arg(long, action = ArgAction::Append, value_name = "REGEX")]
+    pub exclude_regex: Vec<String>,
+
+    /// Match --include / --exclude globs without regard to case
+    #[arg(long)]
+    pub glob_case_insensitive: bool,
+
+    /// Keep only files of at least SIZE bytes (units: KB, MB, GB, TB, KiB..TiB)
+    #[arg(long, value_name = "SIZE", value_parser = parse_size)]
+    pub min_size: Option<u64>,
+
+    /// Keep only files of at most SIZE bytes
+    #[arg(long, value_name = "SIZE", value_parser = parse_size)]
+    pub max_size: Option<u64>,
+
+    /// Keep only files modified at or after TIME (ISO-8601, or a duration ago like 24h or 7d)
+    #[arg(long, value_name = "TIME", value_parser = parse_time_bound)]
+    pub newer_than: Option<DateTime<Utc>>,
+
+    /// Keep only files modified before TIME
+    #[arg(long, value_name = "TIME", value_parser = parse_time_bound)]
+    pub older_than: Option<DateTime<Utc>>,
+
     /// Do not follow symlinks
     #[arg(long)]
     pub no_follow: bool,
 
+    /// Followed links whose target leaves every root: follow (warn), within-root, or refuse
+    #[arg(long, value_enum, value_name = "POLICY", default_value = "follow")]
+    pub symlink_policy: SymlinkPolicy,
+
+    /// Do not read `.vacuumignore` (or `.gitignore`) files
+    #[arg(long)]
+    pub no_ignore: bool,
+
+    /// Also prune paths matched by `.gitignore` files
+    #[arg(long)]
+    pub gitignore: bool,
+
+    /// Detect MIME types from leading magic bytes into `mime_sniffed`
+    #[arg(long)]
+    pub sniff: bool,
+
+    /// Record mode, uid/gid, inode, dev, and nlink for each file
+    #[arg(long)]
+    pub posix: bool,
+
+    /// Add `user` / `group` names resolved from /etc/passwd and /etc/group
+    #[arg(long, requires = "posix")]
+    pub owner_names: bool,
+
+    /// Record inode change time (`ctime`) and birth time (`btime`, null when unsupported)
+    #[arg(long)]
+    pub times: bool,
+
+    /// Record last access time (`atime`); not stable across runs
+    #[arg(long)]
+    pub atime: bool,
+
+    /// Also emit records for directories (`file_type: "dir"`), including empty ones
+    #[arg(long)]
+    pub emit_dirs: bool,
+
+    /// Do not descend into directories on another filesystem; mark each mount point skipped
+    #[arg(long)]
+    pub one_file_system: bool,
+
+    /// Refuse when entries more than N directories below a root would be recorded
+    #[arg(long, value_name = "N")]
+    pub max_depth: Option<usize>,
+
+    /// Refuse once the scan discovers more than N files
+    #[arg(long, value_name = "N")]
+    pub max_files: Option<u64>,
+
+    /// Worker threads for directory reads and metadata calls
+    #[arg(long, value_name = "N", default_value = "1")]
+    pub threads: NonZeroUsize,
+
+    /// Records held in memory before sorted runs spill to disk
+    #[arg(long, value_name = "RECORDS", default_value = "250000")]
+    pub sort_buffer: NonZeroUsize,
+
+    /// Directory for spilled sort runs (default: system temp dir)
+    #[arg(long, value_name = "DIR")]
+    pub spill_dir: Option<PathBuf>,
+
     /// Suppress witness ledger recording
     #[arg(long)]
     pub no_witness: bool,
 
+    /// Manifest encoding on stdout: jsonl, csv, tsv, or mtree (one root only)
+    #[arg(long, value_enum, value_name = "FORMAT", default_value = "jsonl")]
+    pub format: OutputFormat,
+
+    /// Render for a terminal instead: --human (aligned table) or --human=tree
+    #[arg(
+        long,
+        value_enum,
+        value_name = "LAYOUT",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "table",
+        conflicts_with_all = ["format", "json"]
+    )]
+    pub human: Option<HumanLayout>,
+
     /// Accept explicit machine-output intent; scan stdout is already JSONL
     #[arg(long)]
     pub json: bool,
@@ -59,6 +180,9 @@ pub struct Cli {
     pub version: bool,
 }
 
+/// The manifest subcommands (`diff`, `summarize`, `export`, `import-mtree`,
+/// `verify`) print a single refusal envelope and exit 2 when they cannot run.
+/// Only scans are witnessed: no subcommand appends to the witness ledger.
 #[derive(Debug, Subcommand)]
 pub enum Command {
     /// Query the ambient witness ledger
@@ -66,6 +190,48 @@ pub enum Command {
         #[command(subcommand)]
         action: WitnessAction,
     },
+    /// Compare two vacuum.v0 manifests and emit JSONL change events
+    Diff {
+        /// Earlier manifest
+        #[arg(value_name = "OLD")]
+        old: PathBuf,
+        /// Later manifest
+        #[arg(value_name = "NEW")]
+        new: PathBuf,
+        /// Join records on relative_path alone, ignoring the scan root
+        #[arg(long)]
+        ignore_root: bool,
+    },
+    /// Roll a vacuum.v0 manifest up into counts, bytes, and histograms
+    Summarize {
+        /// Manifest produced by an earlier scan
+        #[arg(value_name = "MANIFEST")]
+        manifest: PathBuf,
+    },
+    /// Write a vacuum.v0 manifest into a queryable SQLite database
+    Export {
+        /// Manifest produced by an earlier scan
+        #[arg(value_name = "MANIFEST")]
+        manifest: PathBuf,
+        /// Database to create; an existing file is replaced once the export completes
+        #[arg(long, value_name = "OUT.db")]
+        sqlite: PathBuf,
+    },
+    /// Turn an mtree(5) specification into vacuum.v0 records
+    ImportMtree {
+        /// mtree specification, e.g. from `mtree -c` or `bsdtar --format mtree`
+        #[arg(value_name = "SPEC")]
+        spec: PathBuf,
+        /// Directory the spec's paths are relative to
+        #[arg(long, value_name = "ROOT")]
+        root: PathBuf,
+    },
+    /// Re-scan the roots of a vacuum.v0 manifest and report drift
+    Verify {
+        /// Manifest produced by an earlier scan
+        #[arg(value_name = "MANIFEST")]
+        manifest: PathBuf,
+    },
     /// Run read-only diagnostics and agent discovery
     Doctor {
         /// Emit machine-readable triage JSON for agents
@@ -149,6 +315,12 @@ pub enum WitnessAction {
         #[arg(long)]
         json: bool,
     },
+    /// Check ledger ids and prev links for tampering, gaps, forks, and reordering
+    Verify {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
     /// Count matching witness ledger records
     Count {
         /// Filter by tool name
@@ -173,5 +345,39 @@ pub enum WitnessAction {
 }
 
 pub fn parse() -> Result<Cli, clap::Error> {
-    Cli::try_parse()
+    let cli = Cli::try_parse()?;
+    if cli.files_from.is_some() && cli.roots.len() > 1 {
+        return Err(Cli::command().error(
+            ErrorKind::TooManyValues,
+            "--files-from paths are relative to exactly one ROOT",
+        ));
+    }
+    if cli.format == OutputFormat::Mtree && (cli.roots.len() > 1 || cli.roots_from.is_some()) {
+        return Err(Cli::command().error(
+            ErrorKind::TooManyValues,
+            "--format mtree describes exactly one ROOT",
+        ));
+    }
+    if let (Some(min_size), Some(max_size)) = (cli.min_size, cli.max_size)
+        && min_size > max_size
+    {
+        return Err(Cli::command().error(
+            ErrorKind::ArgumentConflict,
+            format!("--min-size ({min_size} bytes) is larger than --max-size ({max_size} bytes)"),
+        ));
+    }
+    if let (Some(newer_than), Some(older_than)) = (cli.newer_than, cli.older_than)
+        && newer_than > older_than
+    {
+        let timestamp = |time: DateTime<Utc>| time.to_rfc3339_opts(SecondsFormat::Millis, true);
+        return Err(Cli::command().error(
+            ErrorKind::ArgumentConflict,
+            format!(
+                "--newer-than ({}) is later than --older-than ({})",
+                timestamp(newer_than),
+                timestamp(older_than)
+            ),
+        ));
+    }
+    Ok(cli)
 }
@@ -0,0 +1,93 @@
+use std::{
+    fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    refusal::payload::{Refusal, list_refusal},
+    witness::record::WitnessInput,
+};
+
+/// Paths read by `--roots-from` or `--files-from`, plus the list itself as a
+/// witness input.
+pub struct PathList {
+    pub paths: Vec<PathBuf>,
+    pub input: WitnessInput,
+}
+
+/// Reads `path`, or stdin when it is `-`. `field` names the list in the
+/// `E_IO` refusal detail when it cannot be read.
+pub fn read_path_list(path: &Path, field: &str) -> Result<PathList, Refusal> {
+    let bytes = if path == Path::new("-") {
+        let mut bytes = Vec::new();
+        io::stdin().lock().read_to_end(&mut bytes).map(|_| bytes)
+    } else {
+        fs::read(path)
+    }
+    .map_err(|error| list_refusal(field, path, &error))?;
+
+    Ok(PathList {
+        paths: parse_path_list(&bytes),
+        input: WitnessInput {
+            path: path.to_string_lossy().into_owned(),
+            hash: Some(format!("blake3:{}", blake3::hash(&bytes).to_hex())),
+            bytes: Some(bytes.len() as u64),
+        },
+    })
+}
+
+/// Splits on NUL when the list contains one (`find -print0`), otherwise on
+/// newlines. Blank entries are dropped; no other trimming is done, so paths
+/// with leading or trailing spaces survive.
+pub fn parse_path_list(bytes: &[u8]) -> Vec<PathBuf> {
+    if bytes.contains(&0) {
+        bytes
+            .split(|byte| *byte == 0)
+            .filter(|entry| !entry.is_empty())
+            .map(path_from_bytes)
+            .collect()
+    } else {
+        bytes
+            .split(|byte| *byte == b'\n')
+            .map(|line| line.strip_suffix(b"\r").unwrap_or(line))
+            .filter(|line| !line.is_empty())
+            .map(path_from_bytes)
+            .collect()
+    }
+}
+
+#[cfg(unix)]
+fn path_from_bytes(bytes: &[u8]) -> PathBuf {
+    use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+    PathBuf::from(OsStr::from_bytes(bytes))
+}
+
+#[cfg(not(unix))]
+fn path_from_bytes(bytes: &[u8]) -> PathBuf {
+    PathBuf::from(String::from_utf8_lossy(bytes).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::parse_path_list;
+
+    #[test]
+    fn newline_lists_skip_blank_lines_and_carriage_returns() {
+        assert_eq!(
+            parse_path_list(b"/data/a\r\n\n/data/b c \n"),
+            vec![PathBuf::from("/data/a"), PathBuf::from("/data/b c ")]
+        );
+    }
+
+    #[test]
+    fn nul_lists_keep_newlines_inside_paths() {
+        assert_eq!(
+            parse_path_list(b"/data/a\0/data/odd\nname\0"),
+            vec![PathBuf::from("/data/a"), PathBuf::from("/data/odd\nname")]
+        );
+    }
+}
@@ -0,0 +1,311 @@
+use std::{
+    collections::BTreeMap,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use serde::Serialize;
+use serde_json::{Value, json};
+
+use crate::{
+    cli::exit,
+    record::{
+        builder::{VacuumRecord, Warning},
+        manifest::{bad_manifest, read_manifest},
+    },
+    refusal::payload::{Refusal, render},
+};
+
+const DIFFERS_EXIT: u8 = 1;
+
+/// Compares two manifests and prints one JSONL event per change. Exits 0 when
+/// the manifests are identical, 1 when they differ, 2 on refusal.
+pub fn dispatch(old: &Path, new: &Path, ignore_root: bool) -> u8 {
+    let events = match load(old, ignore_root)
+        .and_then(|old| Ok((old, load(new, ignore_root)?)))
+        .map(|(old, new)| diff_records(old, new))
+    {
+        Ok(events) => events,
+        Err(refusal) => {
+            println!("{}", render(&refusal));
+            return exit::REFUSAL;
+        }
+    };
+
+    emit_events(&events)
+}
+
+/// Prints `events` as JSONL and maps them to the diff exit code.
+pub(crate) fn emit_events(events: &[DiffEvent]) -> u8 {
+    if let Err(error) = write_events(events, &mut BufWriter::new(io::stdout().lock())) {
+        eprintln!("vacuum: diff emission failed: {error}");
+        return exit::REFUSAL;
+    }
+
+    if events.is_empty() {
+        exit::SCAN_COMPLETE
+    } else {
+        DIFFERS_EXIT
+    }
+}
+
+/// Join key: `(relative_path, root)` by default, or `(relative_path, "")` when
+/// roots are ignored.
+pub(crate) type DiffKey = (String, String);
+
+fn load(path: &Path, ignore_root: bool) -> Result<BTreeMap<DiffKey, VacuumRecord>, Refusal> {
+    key_records(path, read_manifest(path)?, ignore_root)
+}
+
+/// Keys the records read from `path`, refusing on a duplicate join key.
+pub(crate) fn key_records(
+    path: &Path,
+    records: Vec<VacuumRecord>,
+    ignore_root: bool,
+) -> Result<BTreeMap<DiffKey, VacuumRecord>, Refusal> {
+    let mut keyed = BTreeMap::new();
+    for (index, record) in records.into_iter().enumerate() {
+        let root = if ignore_root {
+            String::new()
+        } else {
+            record.root.clone()
+        };
+        let key = (record.relative_path.clone(), root);
+        if keyed.contains_key(&key) {
+            let error = format!(
+                "duplicate record for relative_path {:?}{}",
+                record.relative_path,
+                if ignore_root {
+                    "; manifests with colliding paths across roots need the default (relative_path, root) key"
+                } else {
+                    ""
+                }
+            );
+            return Err(bad_manifest(path, index + 1, &error));
+        }
+        keyed.insert(key, record);
+    }
+    Ok(keyed)
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct DiffEvent {
+    pub event: &'static str,
+    pub relative_path: String,
+    pub root: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub record: Option<VacuumRecord>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub warnings: Option<Vec<Warning>>,
+}
+
+impl DiffEvent {
+    fn new(event: &'static str, record: &VacuumRecord) -> Self {
+        Self {
+            event,
+            relative_path: record.relative_path.clone(),
+            root: record.root.clone(),
+            record: None,
+            old: None,
+            new: None,
+            warnings: None,
+        }
+    }
+
+    fn changed(event: &'static str, record: &VacuumRecord, old: Value, new: Value) -> Self {
+        Self {
+            old: Some(old),
+            new: Some(new),
+            ..Self::new(event, record)
+        }
+    }
+}
+
+/// Events come out in key order; a key that changed in several ways yields
+/// `size_changed` before `mtime_changed`. Records that became skipped report
+/// only `became_skipped`, since their size and mtime are unknown.
+pub(crate) fn diff_records(
+    old: BTreeMap<DiffKey, VacuumRecord>,
+    mut new: BTreeMap<DiffKey, VacuumRecord>,
+) -> Vec<DiffEvent> {
+    let mut events = Vec::new();
+
+    for (key, old_record) in old {
+        let Some(new_record) = new.remove(&key) else {
+            events.push((
+                key,
+                DiffEvent {
+                    record: Some(old_record.clone()),
+                    ..DiffEvent::new("removed", &old_record)
+                },
+            ));
+            continue;
+        };
+
+        let old_skipped = old_record._skipped == Some(true);
+        let new_skipped = new_record._skipped == Some(true);
+        if new_skipped && !old_skipped {
+            events.push((
+                key,
+                DiffEvent {
+                    warnings: new_record._warnings.clone(),
+                    ..DiffEvent::new("became_skipped", &new_record)
+                },
+            ));
+            continue;
+        }
+        if old_record.size != new_record.size {
+            events.push((
+                key.clone(),
+                DiffEvent::changed(
+                    "size_changed",
+                    &new_record,
+                    json!(old_record.size),
+                    json!(new_record.size),
+                ),
+            ));
+        }
+        if old_record.mtime != new_record.mtime {
+            events.push((
+                key,
+                DiffEvent::changed(
+                    "mtime_changed",
+                    &new_record,
+                    json!(old_record.mtime),
+                    json!(new_record.mtime),
+                ),
+            ));
+        }
+    }
+
+    for (key, new_record) in new {
+        events.push((
+            key,
+            DiffEvent {
+                record: Some(new_record.clone()),
+                ..DiffEvent::new("added", &new_record)
+            },
+        ));
+    }
+
+    // Stable sort keeps the per-key event order established above.
+    events.sort_by(|(left, _), (right, _)| left.cmp(right));
+    events.into_iter().map(|(_, event)| event).collect()
+}
+
+fn write_events<W: Write>(events: &[DiffEvent], writer: &mut W) -> io::Result<()> {
+    for event in events {
+        serde_json::to_writer(&mut *writer, event).map_err(io::Error::other)?;
+        writer.write_all(b"\n")?;
+    }
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use crate::record::builder::VacuumRecord;
+
+    use super::{DiffKey, diff_records};
+
+    fn record(relative_path: &str, root: &str, size: u64, mtime: &str) -> VacuumRecord {
+        let mut record = VacuumRecord::empty();
+        record.path = format!("{root}/{relative_path}");
+        record.relative_path = relative_path.to_string();
+        record.root = root.to_string();
+        record.size = Some(size);
+        record.mtime = Some(mtime.to_string());
+        record
+    }
+
+    fn keyed(records: Vec<VacuumRecord>, ignore_root: bool) -> BTreeMap<DiffKey, VacuumRecord> {
+        records
+            .into_iter()
+            .map(|record| {
+                let root = if ignore_root {
+                    String::new()
+                } else {
+                    record.root.clone()
+                };
+                ((record.relative_path.clone(), root), record)
+            })
+            .collect()
+    }
+
+    fn event_names(
+        old: Vec<VacuumRecord>,
+        new: Vec<VacuumRecord>,
+        ignore_root: bool,
+    ) -> Vec<(String, &'static str)> {
+        diff_records(keyed(old, ignore_root), keyed(new, ignore_root))
+            .into_iter()
+            .map(|event| (event.relative_path, event.event))
+            .collect()
+    }
+
+    #[test]
+    fn identical_manifests_produce_no_events() {
+        let records = vec![record("a.csv", "/r", 1, "2026-01-01T00:00:00.000Z")];
+
+        assert!(event_names(records.clone(), records, false).is_empty());
+    }
+
+    #[test]
+    fn changes_are_reported_in_key_order() {
+        let old = vec![
+            record("a.csv", "/r", 1, "2026-01-01T00:00:00.000Z"),
+            record("b.csv", "/r", 2, "2026-01-01T00:00:00.000Z"),
+            record("d.csv", "/r", 4, "2026-01-01T00:00:00.000Z"),
+        ];
+        let mut skipped = record("d.csv", "/r", 0, "");
+        skipped.size = None;
+        skipped.mtime = None;
+        skipped._skipped = Some(true);
+        let new = vec![
+            record("b.csv", "/r", 20, "2026-02-01T00:00:00.000Z"),
+            record("c.csv", "/r", 3, "2026-01-01T00:00:00.000Z"),
+            skipped,
+        ];
+
+        assert_eq!(
+            event_names(old, new, false),
+            vec![
+                ("a.csv".to_string(), "removed"),
+                ("b.csv".to_string(), "size_changed"),
+                ("b.csv".to_string(), "mtime_changed"),
+                ("c.csv".to_string(), "added"),
+                ("d.csv".to_string(), "became_skipped"),
+            ]
+        );
+    }
+
+    #[test]
+    fn root_changes_only_matter_with_identity_key() {
+        let old = vec![record(
+            "a.csv",
+            "/delivery-01",
+            1,
+            "2026-01-01T00:00:00.000Z",
+        )];
+        let new = vec![record(
+            "a.csv",
+            "/delivery-02",
+            1,
+            "2026-01-01T00:00:00.000Z",
+        )];
+
+        assert_eq!(
+            event_names(old.clone(), new.clone(), false),
+            vec![
+                ("a.csv".to_string(), "removed"),
+                ("a.csv".to_string(), "added")
+            ]
+        );
+        assert!(event_names(old, new, true).is_empty());
+    }
+}
@@ -174,6 +174,7 @@ fn capabilities_report() -> Value {
             "doctor_stderr": "unused on successful doctor commands",
             "exit_codes": {
                 "0": "SCAN_COMPLETE or successful read-only discovery command",
+                "1": "diff/verify found differences, witness verify found a broken chain, or a witness query matched nothing",
                 "2": "REFUSAL or CLI usage error"
             }
         },
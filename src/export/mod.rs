@@ -0,0 +1,15 @@
+#[cfg(feature = "sqlite")]
+mod sqlite;
+
+#[cfg(feature = "sqlite")]
+pub use sqlite::{SQLITE_SCHEMA_VERSION, dispatch_sqlite, export_sqlite};
+
+/// Builds without the `sqlite` feature carry no SQLite library, so
+/// `vacuum export` refuses and says how to get one that does.
+#[cfg(not(feature = "sqlite"))]
+pub fn dispatch_sqlite(_manifest: &std::path::Path, _out: &std::path::Path) -> u8 {
+    eprintln!("vacuum export --sqlite is not available: this build has no SQLite support.");
+    eprintln!("Rebuild with the `sqlite` feature:");
+    eprintln!("  cargo build --release --features sqlite");
+    crate::cli::exit::REFUSAL
+}
@@ -0,0 +1,342 @@
+use std::{fs, path::Path};
+
+use rusqlite::{CachedStatement, Connection, params};
+use serde_json::json;
+
+use crate::{
+    cli::exit,
+    record::{
+        builder::{RECORD_VERSION, VacuumRecord},
+        manifest::parse_manifest,
+        path::native_string,
+    },
+    refusal::{
+        codes::RefusalCode,
+        payload::{Refusal, render},
+    },
+    witness::query::find_run_by_output_hash,
+};
+
+pub const SQLITE_SCHEMA_VERSION: &str = "vacuum.sqlite.v0";
+
+const SCHEMA: &str = "
+CREATE TABLE metadata (
+    key TEXT PRIMARY KEY,
+    value TEXT
+);
+CREATE TABLE records (
+    id INTEGER PRIMARY KEY,
+    path TEXT NOT NULL,
+    relative_path TEXT NOT NULL,
+    root TEXT NOT NULL,
+    size INTEGER,
+    mtime TEXT,
+    ctime TEXT,
+    btime TEXT,
+    atime TEXT,
+    extension TEXT,
+    mime_guess TEXT,
+    mime_sniffed TEXT,
+    file_type TEXT,
+    symlink_target TEXT,
+    symlink_resolved TEXT,
+    symlink_escapes_root INTEGER,
+    mode TEXT,
+    uid INTEGER,
+    gid INTEGER,
+    user TEXT,
+    \"group\" TEXT,
+    inode INTEGER,
+    dev INTEGER,
+    nlink INTEGER,
+    skipped INTEGER NOT NULL
+);
+CREATE TABLE warnings (
+    record_id INTEGER NOT NULL REFERENCES records(id),
+    position INTEGER NOT NULL,
+    tool TEXT NOT NULL,
+    code TEXT NOT NULL,
+    message TEXT NOT NULL,
+    detail TEXT NOT NULL,
+    PRIMARY KEY (record_id, position)
+);
+CREATE TABLE tool_versions (
+    record_id INTEGER NOT NULL REFERENCES records(id),
+    tool TEXT NOT NULL,
+    version TEXT NOT NULL,
+    PRIMARY KEY (record_id, tool)
+);
+CREATE INDEX records_relative_path ON records(relative_path);
+CREATE INDEX records_root ON records(root);
+CREATE INDEX records_extension ON records(extension);
+CREATE INDEX records_mtime ON records(mtime);
+CREATE INDEX warnings_code ON warnings(code);
+";
+
+/// `vacuum export --sqlite OUT MANIFEST`: writes the manifest into a fresh
+/// SQLite database at `out`, replacing any file there only once the database
+/// is complete.
+pub fn dispatch_sqlite(manifest: &Path, out: &Path) -> u8 {
+    let find_run = |output_hash: &str| {
+        find_run_by_output_hash(output_hash).and_then(|run| {
+            run.get("id")
+                .and_then(serde_json::Value::as_str)
+                .map(str::to_string)
+        })
+    };
+    match export_sqlite(manifest, out, find_run) {
+        Ok(()) => exit::SCAN_COMPLETE,
+        Err(refusal) => {
+            println!("{}", render(&refusal));
+            exit::REFUSAL
+        }
+    }
+}
+
+/// `metadata.output_hash` is the BLAKE3 of the manifest bytes, which is the
+/// witness `output_hash` of the scan that wrote them; `metadata.witness_id` is
+/// whatever `find_run` returns for that hash.
+pub fn export_sqlite(
+    manifest: &Path,
+    out: &Path,
+    find_run: impl Fn(&str) -> Option<String>,
+) -> Result<(), Refusal> {
+    let bytes = fs::read(manifest).map_err(|error| {
+        Refusal::new(
+            RefusalCode::Io,
+            json!({
+                "manifest": manifest.display().to_string(),
+                "error": error.to_string(),
+            }),
+        )
+    })?;
+    let output_hash = format!("blake3:{}", blake3::hash(&bytes).to_hex());
+    let records = parse_manifest(manifest, &bytes)?;
+    let witness_id = find_run(&output_hash);
+
+    let sqlite_refusal = |error: String| {
+        Refusal::new(
+            RefusalCode::Io,
+            json!({ "sqlite": out.display().to_string(), "error": error }),
+        )
+    };
+    let mut staging = out.as_os_str().to_owned();
+    staging.push(".partial");
+    let staging = Path::new(&staging);
+    // A leftover from an interrupted export would make CREATE TABLE fail.
+    let _ = fs::remove_file(staging);
+
+    let written = write_database(
+        staging,
+        &records,
+        &[
+            ("schema_version", Some(SQLITE_SCHEMA_VERSION.to_string())),
+            ("record_version", Some(RECORD_VERSION.to_string())),
+            ("manifest", Some(native_string(manifest))),
+            ("output_hash", Some(output_hash)),
+            ("witness_id", witness_id),
+            ("records", Some(records.len().to_string())),
+            (
+                "exported_by",
+                Some(format!("vacuum {}", env!("CARGO_PKG_VERSION"))),
+            ),
+        ],
+    )
+    .map_err(|error| error.to_string())
+    .and_then(|()| fs::rename(staging, out).map_err(|error| error.to_string()));
+    if let Err(error) = written {
+        let _ = fs::remove_file(staging);
+        return Err(sqlite_refusal(error));
+    }
+    Ok(())
+}
+
+fn write_database(
+    path: &Path,
+    records: &[VacuumRecord],
+    metadata: &[(&str, Option<String>)],
+) -> rusqlite::Result<()> {
+    let mut connection = Connection::open(path)?;
+    let transaction = connection.transaction()?;
+    transaction.execute_batch(SCHEMA)?;
+    for (key, value) in metadata {
+        transaction.execute(
+            "INSERT INTO metadata (key, value) VALUES (?1, ?2)",
+            params![key, value],
+        )?;
+    }
+    {
+        let mut inserts = Inserts {
+            record: transaction.prepare_cached(INSERT_RECORD)?,
+            warning: transaction.prepare_cached(INSERT_WARNING)?,
+            tool_version: transaction.prepare_cached(INSERT_TOOL_VERSION)?,
+        };
+        for (index, record) in records.iter().enumerate() {
+            inserts.add(to_integer(index + 1)?, record)?;
+        }
+    }
+    transaction.commit()?;
+    connection.close().map_err(|(_, error)| error)
+}
+
+const INSERT_RECORD: &str = "INSERT INTO records (
+    id, path, relative_path, root, size, mtime, ctime, btime, atime,
+    extension, mime_guess, mime_sniffed, file_type,
+    symlink_target, symlink_resolved, symlink_escapes_root,
+    mode, uid, gid, user, \"group\", inode, dev, nlink, skipped
+) VALUES (
+    ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13,
+    ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24, ?25
+)";
+
+const INSERT_WARNING: &str = "INSERT INTO warnings (
+    record_id, position, tool, code, message, detail
+) VALUES (?1, ?2, ?3, ?4, ?5, ?6)";
+
+const INSERT_TOOL_VERSION: &str =
+    "INSERT INTO tool_versions (record_id, tool, version) VALUES (?1, ?2, ?3)";
+
+/// The three insert statements, each prepared once per export.
+struct Inserts<'conn> {
+    record: CachedStatement<'conn>,
+    warning: CachedStatement<'conn>,
+    tool_version: CachedStatement<'conn>,
+}
+
+impl Inserts<'_> {
+    /// `records.id` is the record's 1-based position in the manifest.
+    fn add(&mut self, id: i64, record: &VacuumRecord) -> rusqlite::Result<()> {
+        let symlink = record.symlink.as_ref();
+        self.record.execute(params![
+            id,
+            record.path,
+            record.relative_path,
+            record.root,
+            record.size.map(to_integer).transpose()?,
+            record.mtime,
+            record.ctime,
+            record.btime.clone().flatten(),
+            record.atime,
+            record.extension,
+            record.mime_guess,
+            record.mime_sniffed,
+            record.file_type,
+            symlink.map(|symlink| symlink.target.as_str()),
+            symlink.and_then(|symlink| symlink.resolved.as_deref()),
+            symlink.map(|symlink| symlink.escapes_root),
+            record.mode,
+            record.uid,
+            record.gid,
+            record.user,
+            record.group,
+            record.inode.map(to_integer).transpose()?,
+            record.dev.map(to_integer).transpose()?,
+            record.nlink.map(to_integer).transpose()?,
+            record._skipped == Some(true),
+        ])?;
+
+        for (position, warning) in record._warnings.iter().flatten().enumerate() {
+            self.warning.execute(params![
+                id,
+                to_integer(position)?,
+                warning.tool,
+                warning.code,
+                warning.message,
+                warning.detail.to_string(),
+            ])?;
+        }
+        for (tool, version) in &record.tool_versions {
+            self.tool_version.execute(params![id, tool, version])?;
+        }
+        Ok(())
+    }
+}
+
+/// SQLite integers are signed 64-bit; a larger value fails the export rather
+/// than being stored wrapped.
+fn to_integer<T>(value: T) -> rusqlite::Result<i64>
+where
+    T: TryInto<i64> + Copy + std::fmt::Display,
+{
+    value
+        .try_into()
+        .map_err(|_| rusqlite::Error::ToSqlConversionFailure(format!("{value} exceeds i64").into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use rusqlite::Connection;
+    use serde_json::json;
+
+    use super::export_sqlite;
+    use crate::{
+        output::jsonl::serialize_sorted_jsonl,
+        record::builder::{VacuumRecord, Warning},
+    };
+
+    #[test]
+    fn records_warnings_and_versions_land_in_their_tables() {
+        let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+        let mut file = VacuumRecord::empty();
+        file.path = "/data/a.csv".to_string();
+        file.relative_path = "a.csv".to_string();
+        file.root = "/data".to_string();
+        file.size = Some(42);
+        file.extension = Some(".csv".to_string());
+        let mut skipped = VacuumRecord::empty();
+        skipped.path = "/data/locked".to_string();
+        skipped.relative_path = "locked".to_string();
+        skipped.root = "/data".to_string();
+        skipped._skipped = Some(true);
+        skipped._warnings = Some(vec![Warning {
+            tool: "vacuum".to_string(),
+            code: "E_IO".to_string(),
+            message: "Permission denied".to_string(),
+            detail: json!({ "error": "Permission denied" }),
+        }]);
+        let manifest = temp_dir.path().join("manifest.jsonl");
+        let lines = serialize_sorted_jsonl(&[skipped, file]);
+        fs::write(&manifest, format!("{}\n", lines.join("\n"))).expect("manifest written");
+        let out = temp_dir.path().join("manifest.db");
+
+        export_sqlite(&manifest, &out, |_| None).expect("export should succeed");
+
+        let connection = Connection::open(&out).expect("database opens");
+        let (relative_path, size, skipped): (String, Option<i64>, bool) = connection
+            .query_row(
+                "SELECT relative_path, size, skipped FROM records WHERE id = 1",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .expect("first record exists");
+        assert_eq!(
+            (relative_path.as_str(), size, skipped),
+            ("a.csv", Some(42), false)
+        );
+        let code: String = connection
+            .query_row(
+                "SELECT w.code FROM warnings w JOIN records r ON r.id = w.record_id
+                 WHERE r.relative_path = 'locked'",
+                [],
+                |row| row.get(0),
+            )
+            .expect("warning row exists");
+        assert_eq!(code, "E_IO");
+        let versions: i64 = connection
+            .query_row("SELECT count(*) FROM tool_versions", [], |row| row.get(0))
+            .expect("count succeeds");
+        assert_eq!(versions, 2);
+        let hash: String = connection
+            .query_row(
+                "SELECT value FROM metadata WHERE key = 'output_hash'",
+                [],
+                |row| row.get(0),
+            )
+            .expect("hash row exists");
+        let bytes = fs::read(&manifest).expect("manifest readable");
+        assert_eq!(hash, format!("blake3:{}", blake3::hash(&bytes).to_hex()));
+        assert!(!temp_dir.path().join("manifest.db.partial").exists());
+    }
+}
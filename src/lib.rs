@@ -1,14 +1,18 @@
 #![forbid(unsafe_code)]
 
-use std::ffi::OsString;
+use std::{ffi::OsString, ops::ControlFlow, sync::Arc};
 
 use serde_json::json;
 
 pub mod cli;
+pub mod diff;
 pub mod doctor;
+pub mod export;
 pub mod output;
 pub mod record;
 pub mod refusal;
+pub mod summary;
+pub mod verify;
 pub mod walk;
 pub mod witness;
 
@@ -17,7 +21,7 @@ pub fn run() -> u8 {
         return handle_display_mode(display_mode);
     }
 
-    let cli = match cli::args::parse() {
+    let mut cli = match cli::args::parse() {
         Ok(cli) => cli,
         Err(error) => return cli::exit::from_clap_error(error),
     };
@@ -41,6 +45,19 @@ pub fn run() -> u8 {
     if let Some(command) = cli.command.as_ref() {
         return match command {
             cli::args::Command::Witness { action } => witness::query::dispatch(action),
+            cli::args::Command::Diff {
+                old,
+                new,
+                ignore_root,
+            } => diff::dispatch(old, new, *ignore_root),
+            cli::args::Command::Summarize { manifest } => summary::dispatch(manifest),
+            cli::args::Command::Export { manifest, sqlite } => {
+                export::dispatch_sqlite(manifest, sqlite)
+            }
+            cli::args::Command::ImportMtree { spec, root } => {
+                record::mtree::dispatch_import(spec, root)
+            }
+            cli::args::Command::Verify { manifest } => verify::dispatch(manifest),
             cli::args::Command::Doctor {
                 robot_triage,
                 json,
@@ -62,86 +79,308 @@ pub fn run() -> u8 {
 
     let _scan_stdout_is_already_jsonl = cli.json;
 
+    let mut listed_files = None;
+    let list_input = if let Some(path) = cli.roots_from.clone() {
+        match cli::list::read_path_list(&path, "roots_from") {
+            Ok(list) => {
+                cli.roots.extend(list.paths);
+                Some(list.input)
+            }
+            Err(refusal) => return refuse(&cli, None, &refusal),
+        }
+    } else if let Some(path) = cli.files_from.clone() {
+        match cli::list::read_path_list(&path, "files_from") {
+            Ok(list) => {
+                listed_files = Some(list.paths);
+                Some(list.input)
+            }
+            Err(refusal) => return refuse(&cli, None, &refusal),
+        }
+    } else {
+        None
+    };
+
     if cli.roots.is_empty() {
-        let refusal = refusal::payload::empty_roots_refusal();
-        let rendered = refusal::payload::render(&refusal);
-        println!("{rendered}");
-        append_witness_record(
+        return refuse(
             &cli,
-            "REFUSAL",
-            cli::exit::REFUSAL,
-            hash_bytes(format!("{rendered}\n").as_bytes()),
+            list_input.as_ref(),
+            &refusal::payload::empty_roots_refusal(),
         );
-        return cli::exit::REFUSAL;
     }
 
     if let Err(refusal) = walk::walker::validate_roots(&cli.roots) {
-        let rendered = refusal::payload::render(&refusal);
-        println!("{rendered}");
+        return refuse(&cli, list_input.as_ref(), &refusal);
+    }
+
+    let ignore_log = Arc::new(walk::ignore::IgnoreLog::default());
+    let scan_options = walk::walker::ScanOptions {
+        follow_symlinks: !cli.no_follow,
+        progress_enabled: cli.progress,
+        threads: cli.threads.get(),
+        max_depth: cli.max_depth,
+        max_files: cli.max_files,
+        sniff: cli.sniff,
+        posix: cli.posix,
+        owner_names: cli.owner_names,
+        times: cli.times,
+        atime: cli.atime,
+        emit_dirs: cli.emit_dirs,
+        symlink_policy: cli.symlink_policy,
+        one_file_system: cli.one_file_system,
+        no_ignore: cli.no_ignore,
+        gitignore: cli.gitignore,
+        exclude: cli.exclude.clone(),
+        glob_case_insensitive: cli.glob_case_insensitive,
+        ignore_log: Some(Arc::clone(&ignore_log)),
+    };
+    let filter = match walk::filter::PathFilter::from_patterns(&walk::filter::PathPatterns {
+        include: &cli.include,
+        exclude: &cli.exclude,
+        include_regex: &cli.include_regex,
+        exclude_regex: &cli.exclude_regex,
+        glob_case_insensitive: cli.glob_case_insensitive,
+    }) {
+        Ok(filter) => filter,
+        Err(refusal) => return refuse(&cli, list_input.as_ref(), &refusal),
+    };
+    let predicates = metadata_filter(&cli);
+    let spill_dir = cli.spill_dir.clone().unwrap_or_else(std::env::temp_dir);
+    let mut sorter = output::sort::ExternalSorter::new(cli.sort_buffer.get(), spill_dir.clone());
+    let mut spill_error = None;
+    let sink = |record: record::builder::VacuumRecord| {
+        if !filter.matches(&record.relative_path) || !predicates.matches(&record) {
+            return ControlFlow::Continue(());
+        }
+        match sorter.push(record) {
+            Ok(()) => ControlFlow::Continue(()),
+            Err(error) => {
+                spill_error = Some(error);
+                ControlFlow::Break(())
+            }
+        }
+    };
+    let scanned = match &listed_files {
+        // `parse` guarantees a single root alongside --files-from.
+        Some(files) => walk::list::scan_file_list_into(&cli.roots[0], files, &scan_options, sink),
+        None => walk::walker::scan_roots_into(&cli.roots, &scan_options, sink),
+    };
+    if let Err(refusal) = scanned {
+        return refuse(&cli, list_input.as_ref(), &refusal);
+    }
+
+    let sorted = match spill_error.map_or_else(|| sorter.finish(), Err) {
+        Ok(sorted) => sorted,
+        Err(error) => {
+            return refuse(
+                &cli,
+                list_input.as_ref(),
+                &refusal::payload::spill_refusal(&spill_dir, &error),
+            );
+        }
+    };
+
+    let mut hasher = blake3::Hasher::new();
+    let color = std::io::IsTerminal::is_terminal(&std::io::stdout())
+        && std::env::var_os("NO_COLOR").is_none();
+    let mut stdout = std::io::BufWriter::new(std::io::stdout().lock());
+    let written = match (cli.human, cli.format) {
+        (Some(layout), _) => output::human::write_human(
+            sorted,
+            layout,
+            &output::human::HumanStyle {
+                color,
+                now: chrono::Utc::now(),
+            },
+            &mut stdout,
+            &mut hasher,
+        ),
+        (None, output::OutputFormat::Jsonl) => {
+            output::jsonl::write_records(sorted, &mut stdout, &mut hasher)
+        }
+        (None, output::OutputFormat::Csv) => output::csv::write_table(
+            sorted,
+            output::csv::Delimiter::Comma,
+            &mut stdout,
+            &mut hasher,
+        ),
+        (None, output::OutputFormat::Tsv) => output::csv::write_table(
+            sorted,
+            output::csv::Delimiter::Tab,
+            &mut stdout,
+            &mut hasher,
+        ),
+        (None, output::OutputFormat::Mtree) => {
+            output::mtree::write_spec(sorted, &mut stdout, &mut hasher)
+        }
+    };
+    let output_hash = format!("blake3:{}", hasher.finalize().to_hex());
+    if let Err(error) = written {
+        eprintln!("vacuum: manifest emission failed: {error}");
         append_witness_record(
             &cli,
+            list_input.as_ref(),
+            Some(&ignore_log),
             "REFUSAL",
             cli::exit::REFUSAL,
-            hash_bytes(format!("{rendered}\n").as_bytes()),
+            output_hash,
         );
         return cli::exit::REFUSAL;
     }
-
-    let scanned = walk::walker::scan_roots_with_progress(&cli.roots, !cli.no_follow, cli.progress);
-    let filtered = walk::filter::apply_filters(scanned, &cli.include, &cli.exclude);
-    let rendered_lines = output::jsonl::serialize_sorted_jsonl(&filtered);
-    for line in &rendered_lines {
-        println!("{line}");
-    }
-    let output_bytes = rendered_lines
-        .iter()
-        .flat_map(|line| {
-            line.as_bytes()
-                .iter()
-                .copied()
-                .chain(std::iter::once(b'\n'))
-        })
-        .collect::<Vec<_>>();
-    let output_hash = hash_bytes(&output_bytes);
-    append_witness_record(&cli, "SCAN_COMPLETE", cli::exit::SCAN_COMPLETE, output_hash);
+    append_witness_record(
+        &cli,
+        list_input.as_ref(),
+        Some(&ignore_log),
+        "SCAN_COMPLETE",
+        cli::exit::SCAN_COMPLETE,
+        output_hash,
+    );
 
     cli::exit::SCAN_COMPLETE
 }
 
-fn append_witness_record(cli: &cli::args::Cli, outcome: &str, exit_code: u8, output_hash: String) {
+fn refuse(
+    cli: &cli::args::Cli,
+    list_input: Option<&witness::record::WitnessInput>,
+    refusal: &refusal::payload::Refusal,
+) -> u8 {
+    let rendered = refusal::payload::render(refusal);
+    println!("{rendered}");
+    append_witness_record(
+        cli,
+        list_input,
+        None,
+        "REFUSAL",
+        cli::exit::REFUSAL,
+        hash_bytes(format!("{rendered}\n").as_bytes()),
+    );
+    cli::exit::REFUSAL
+}
+
+/// `list_input` is the `--roots-from` or `--files-from` list, recorded after
+/// the roots. `ignore_log` is absent when the run refused before or during
+/// the walk.
+fn append_witness_record(
+    cli: &cli::args::Cli,
+    list_input: Option<&witness::record::WitnessInput>,
+    ignore_log: Option<&walk::ignore::IgnoreLog>,
+    outcome: &str,
+    exit_code: u8,
+    output_hash: String,
+) {
     if cli.no_witness {
         return;
     }
 
     let mut record = witness::record::WitnessRecord::from_run(
         &cli.roots,
-        &cli.include,
-        &cli.exclude,
-        cli.no_follow,
+        witness_params(cli, ignore_log),
         outcome,
         exit_code,
         output_hash,
-        witness::ledger::read_prev(),
+        None,
     );
-    record.compute_id();
-    if let Err(error) = witness::ledger::append(&record) {
-        emit_witness_warning(cli.progress, &error);
+    record.inputs.extend(list_input.cloned());
+    match witness::ledger::append(&mut record) {
+        Ok(witness::ledger::AppendLock::Held) => {}
+        Ok(witness::ledger::AppendLock::Skipped(reason)) => emit_witness_warning(
+            cli.progress,
+            &format!("Witness ledger lock not acquired ({reason}); appended unlocked"),
+        ),
+        Err(error) => {
+            emit_witness_warning(cli.progress, &format!("Witness append failed: {error}"));
+        }
+    }
+}
+
+fn metadata_filter(cli: &cli::args::Cli) -> walk::filter::MetadataFilter {
+    walk::filter::MetadataFilter {
+        min_size: cli.min_size,
+        max_size: cli.max_size,
+        newer_than: cli.newer_than,
+        older_than: cli.older_than,
     }
 }
 
-fn emit_witness_warning(progress_enabled: bool, error: &std::io::Error) {
+/// Time bounds are recorded resolved, so a relative `--newer-than 24h` can be
+/// replayed exactly. `scan_roots` are the roots as records name them, so
+/// `vacuum verify` can re-scan a root that produced no records. `ignore_files`
+/// names every ignore file the walk read, with its BLAKE3.
+fn witness_params(
+    cli: &cli::args::Cli,
+    ignore_log: Option<&walk::ignore::IgnoreLog>,
+) -> serde_json::Value {
+    let timestamp = |time: Option<chrono::DateTime<chrono::Utc>>| {
+        time.map(|time| time.to_rfc3339_opts(chrono::SecondsFormat::Millis, true))
+    };
+
+    json!({
+        "roots": cli
+            .roots
+            .iter()
+            .map(|root| root.to_string_lossy().into_owned())
+            .collect::<Vec<_>>(),
+        "scan_roots": cli
+            .roots
+            .iter()
+            .map(|root| record::path::native_string(&walk::walker::absolute_root(root)))
+            .collect::<Vec<_>>(),
+        "roots_from": cli
+            .roots_from
+            .as_ref()
+            .map(|path| path.to_string_lossy().into_owned()),
+        "files_from": cli
+            .files_from
+            .as_ref()
+            .map(|path| path.to_string_lossy().into_owned()),
+        "include": cli.include,
+        "exclude": cli.exclude,
+        "include_regex": cli.include_regex,
+        "exclude_regex": cli.exclude_regex,
+        "glob_case_insensitive": cli.glob_case_insensitive,
+        "min_size": cli.min_size,
+        "max_size": cli.max_size,
+        "newer_than": timestamp(cli.newer_than),
+        "older_than": timestamp(cli.older_than),
+        "no_follow": cli.no_follow,
+        "no_ignore": cli.no_ignore,
+        "gitignore": cli.gitignore,
+        "ignore_files": ignore_log.map_or_else(|| json!([]), walk::ignore::IgnoreLog::to_json),
+        "sniff": cli.sniff,
+        "posix": cli.posix,
+        "owner_names": cli.owner_names,
+        "times": cli.times,
+        "atime": cli.atime,
+        "emit_dirs": cli.emit_dirs,
+        "symlink_policy": cli.symlink_policy.as_str(),
+        "one_file_system": cli.one_file_system,
+        "max_depth": cli.max_depth,
+        "max_files": cli.max_files,
+        "format": cli.format.as_str(),
+        "human": cli.human.map(|layout| layout.as_str()),
+    })
+}
+
+fn emit_witness_warning(progress_enabled: bool, message: &str) {
     if progress_enabled {
         let payload = json!({
             "type": "warning",
             "tool": "vacuum",
-            "message": format!("Witness append failed: {error}"),
+            "message": message,
         });
         eprintln!("{payload}");
     } else {
-        eprintln!("vacuum: witness append failed: {error}");
+        eprintln!("vacuum: {}", lowercase_first(message));
     }
 }
 
+fn lowercase_first(message: &str) -> String {
+    let mut chars = message.chars();
+    chars
+        .next()
+        .map(|first| first.to_lowercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
 fn hash_bytes(bytes: &[u8]) -> String {
     format!("blake3:{}", blake3::hash(bytes).to_hex())
 }
@@ -0,0 +1,223 @@
+use std::io::{self, Write};
+
+use crate::{output::jsonl::write_terminated, record::builder::VacuumRecord};
+
+/// Field separator for [`write_table`]: `--format csv` or `--format tsv`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Delimiter {
+    /// RFC 4180 comma-separated values with a header row and CRLF line ends.
+    Comma,
+    /// Tab-separated values with a header row and backslash-escaped fields.
+    Tab,
+}
+
+/// Fixed column order for `--format csv|tsv`. Every column is always present;
+/// a field the record does not carry is an empty cell.
+pub const COLUMNS: [&str; 26] = [
+    "path",
+    "relative_path",
+    "root",
+    "size",
+    "mtime",
+    "ctime",
+    "btime",
+    "atime",
+    "extension",
+    "mime_guess",
+    "mime_sniffed",
+    "file_type",
+    "symlink_target",
+    "symlink_resolved",
+    "symlink_escapes_root",
+    "mode",
+    "uid",
+    "gid",
+    "user",
+    "group",
+    "inode",
+    "dev",
+    "nlink",
+    "skipped",
+    "warnings",
+    "warnings_json",
+];
+
+/// Writes a header row and one row per sorted record.
+pub(crate) fn write_table<I, W>(
+    records: I,
+    delimiter: Delimiter,
+    writer: &mut W,
+    hasher: &mut blake3::Hasher,
+) -> io::Result<()>
+where
+    I: IntoIterator<Item = io::Result<VacuumRecord>>,
+    W: Write,
+{
+    let terminator: &[u8] = match delimiter {
+        Delimiter::Comma => b"\r\n",
+        Delimiter::Tab => b"\n",
+    };
+    let header = COLUMNS.map(str::to_string);
+    let rows = records
+        .into_iter()
+        .map(|record| Ok(render_row(&row_fields(&record?), delimiter)));
+    write_terminated(
+        std::iter::once(Ok(render_row(&header, delimiter))).chain(rows),
+        terminator,
+        writer,
+        hasher,
+    )
+}
+
+/// Flattens a record into [`COLUMNS`] order. `warnings` lists the codes joined
+/// by `;` for filtering in a spreadsheet; `warnings_json` keeps the full
+/// warning objects as compact JSON.
+fn row_fields(record: &VacuumRecord) -> [String; 26] {
+    let text = |value: Option<&str>| value.unwrap_or_default().to_string();
+    let number = |value: Option<u64>| value.map(|value| value.to_string()).unwrap_or_default();
+    let symlink = record.symlink.as_ref();
+    let warnings = record._warnings.as_deref().unwrap_or_default();
+
+    [
+        record.path.clone(),
+        record.relative_path.clone(),
+        record.root.clone(),
+        number(record.size),
+        text(record.mtime.as_deref()),
+        text(record.ctime.as_deref()),
+        text(record.btime.as_ref().and_then(Option::as_deref)),
+        text(record.atime.as_deref()),
+        text(record.extension.as_deref()),
+        text(record.mime_guess.as_deref()),
+        text(record.mime_sniffed.as_deref()),
+        text(record.file_type.as_deref()),
+        text(symlink.map(|symlink| symlink.target.as_str())),
+        text(symlink.and_then(|symlink| symlink.resolved.as_deref())),
+        symlink
+            .map(|symlink| symlink.escapes_root.to_string())
+            .unwrap_or_default(),
+        text(record.mode.as_deref()),
+        number(record.uid.map(u64::from)),
+        number(record.gid.map(u64::from)),
+        text(record.user.as_deref()),
+        text(record.group.as_deref()),
+        number(record.inode),
+        number(record.dev),
+        number(record.nlink),
+        if record._skipped == Some(true) {
+            "true".to_string()
+        } else {
+            String::new()
+        },
+        warnings
+            .iter()
+            .map(|warning| warning.code.as_str())
+            .collect::<Vec<_>>()
+            .join(";"),
+        if warnings.is_empty() {
+            String::new()
+        } else {
+            serde_json::to_string(warnings).unwrap_or_default()
+        },
+    ]
+}
+
+fn render_row(fields: &[String], delimiter: Delimiter) -> String {
+    match delimiter {
+        Delimiter::Comma => fields
+            .iter()
+            .map(|field| quote_csv(field))
+            .collect::<Vec<_>>()
+            .join(","),
+        Delimiter::Tab => fields
+            .iter()
+            .map(|field| escape_tsv(field))
+            .collect::<Vec<_>>()
+            .join("\t"),
+    }
+}
+
+/// RFC 4180: fields holding a comma, quote, CR, or LF are wrapped in quotes
+/// with embedded quotes doubled; everything else is written bare.
+fn quote_csv(field: &str) -> String {
+    if field.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// TSV has no quoting, so tab, CR, LF, and backslash are written as `\t`,
+/// `\r`, `\n`, and `\\`.
+fn escape_tsv(field: &str) -> String {
+    let mut escaped = String::with_capacity(field.len());
+    for character in field.chars() {
+        match character {
+            '\t' => escaped.push_str("\\t"),
+            '\r' => escaped.push_str("\\r"),
+            '\n' => escaped.push_str("\\n"),
+            '\\' => escaped.push_str("\\\\"),
+            other => escaped.push(other),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{COLUMNS, Delimiter, escape_tsv, quote_csv, write_table};
+    use crate::record::builder::{VacuumRecord, Warning};
+
+    fn table(records: &[VacuumRecord], delimiter: Delimiter) -> String {
+        let mut written = Vec::new();
+        write_table(
+            records.iter().cloned().map(Ok),
+            delimiter,
+            &mut written,
+            &mut blake3::Hasher::new(),
+        )
+        .expect("table should be written");
+        String::from_utf8(written).expect("table is utf-8")
+    }
+
+    #[test]
+    fn csv_quoting_follows_rfc_4180() {
+        assert_eq!(quote_csv("plain.csv"), "plain.csv");
+        assert_eq!(quote_csv("a,b.csv"), "\"a,b.csv\"");
+        assert_eq!(quote_csv("say \"hi\".txt"), "\"say \"\"hi\"\".txt\"");
+        assert_eq!(quote_csv("line\nbreak"), "\"line\nbreak\"");
+        assert_eq!(escape_tsv("tab\there\\"), "tab\\there\\\\");
+    }
+
+    #[test]
+    fn rows_follow_the_fixed_columns_and_flatten_warnings() {
+        let mut record = VacuumRecord::empty();
+        record.path = "/data/a,b.csv".to_string();
+        record.relative_path = "a,b.csv".to_string();
+        record.root = "/data".to_string();
+        record._skipped = Some(true);
+        record._warnings = Some(vec![Warning {
+            tool: "vacuum".to_string(),
+            code: "E_IO".to_string(),
+            message: "Permission denied".to_string(),
+            detail: json!({ "path": "/data/a,b.csv" }),
+        }]);
+
+        let csv = table(std::slice::from_ref(&record), Delimiter::Comma);
+        let lines = csv.split("\r\n").collect::<Vec<_>>();
+        assert_eq!(lines[0], COLUMNS.join(","));
+        assert!(lines[1].starts_with("\"/data/a,b.csv\",\"a,b.csv\",/data,,"));
+        assert!(lines[1].ends_with(
+            ",true,E_IO,\"[{\"\"tool\"\":\"\"vacuum\"\",\"\"code\"\":\"\"E_IO\"\",\"\"message\"\":\"\"Permission denied\"\",\"\"detail\"\":{\"\"path\"\":\"\"/data/a,b.csv\"\"}}]\""
+        ));
+        assert_eq!(lines[2], "");
+
+        let tsv = table(&[record], Delimiter::Tab);
+        let rows = tsv.lines().collect::<Vec<_>>();
+        assert_eq!(rows[0], COLUMNS.join("\t"));
+        assert_eq!(rows[1].split('\t').count(), COLUMNS.len());
+        assert!(rows[1].starts_with("/data/a,b.csv\ta,b.csv\t/data\t\t"));
+    }
+}
@@ -0,0 +1,452 @@
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    io::{self, Write},
+};
+
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+
+use crate::{output::jsonl::write_lines, record::builder::VacuumRecord, summary::Summary};
+
+/// Layout for `--human`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum HumanLayout {
+    /// One aligned row per record.
+    #[default]
+    Table,
+    /// Records nested under their directories, one tree per root.
+    Tree,
+}
+
+impl HumanLayout {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Table => "table",
+            Self::Tree => "tree",
+        }
+    }
+}
+
+/// Rendering context that is not part of the records: whether to use ANSI
+/// color, and the instant relative mtimes are measured from.
+pub(crate) struct HumanStyle {
+    pub color: bool,
+    pub now: DateTime<Utc>,
+}
+
+const RED: &str = "\x1b[31m";
+const YELLOW: &str = "\x1b[33m";
+const RESET: &str = "\x1b[0m";
+
+/// Renders the sorted records for a terminal, followed by a totals footer.
+/// Unlike the other formats, this holds every record in memory, since tree
+/// output and column widths need the whole scan; `--sort-buffer` does not
+/// bound it.
+pub(crate) fn write_human<I, W>(
+    records: I,
+    layout: HumanLayout,
+    style: &HumanStyle,
+    writer: &mut W,
+    hasher: &mut blake3::Hasher,
+) -> io::Result<()>
+where
+    I: IntoIterator<Item = io::Result<VacuumRecord>>,
+    W: Write,
+{
+    let records = records.into_iter().collect::<io::Result<Vec<_>>>()?;
+
+    let mut rendered = match layout {
+        HumanLayout::Table => render_table(&records, style),
+        HumanLayout::Tree => render_tree(&records, style),
+    };
+    let mut summary = Summary::default();
+    for record in &records {
+        summary.add(record);
+    }
+    rendered.push(String::new());
+    rendered.push(footer(&summary, style));
+    write_lines(rendered.into_iter().map(Ok), writer, hasher)
+}
+
+fn render_table(records: &[VacuumRecord], style: &HumanStyle) -> Vec<String> {
+    let multi_root = records
+        .first()
+        .is_some_and(|first| records.iter().any(|record| record.root != first.root));
+    let root_width = if multi_root {
+        records
+            .iter()
+            .map(|record| record.root.chars().count())
+            .max()
+            .unwrap_or_default()
+            .max("ROOT".len())
+    } else {
+        0
+    };
+    let row = |size: &str, modified: &str, kind: &str, root: &str, path: &str| {
+        let root = if multi_root {
+            format!("{root:<root_width$}  ")
+        } else {
+            String::new()
+        };
+        format!("{size:>10}  {modified:<10}  {kind:<7}  {root}{path}")
+    };
+
+    let mut lines = vec![row("SIZE", "MODIFIED", "TYPE", "ROOT", "PATH")];
+    for record in records {
+        let skipped = record._skipped == Some(true);
+        let kind = if skipped {
+            "skipped"
+        } else {
+            record.file_type.as_deref().unwrap_or("?")
+        };
+        let line = row(
+            &record
+                .size
+                .map(human_size)
+                .unwrap_or_else(|| "-".to_string()),
+            &record
+                .mtime
+                .as_deref()
+                .and_then(|mtime| relative_time(mtime, style.now))
+                .unwrap_or_else(|| "-".to_string()),
+            kind,
+            &printable(&record.root),
+            &printable(&record.relative_path),
+        );
+        lines.push(highlight(
+            format!("{line}{}", warning_suffix(record)),
+            record,
+            style,
+        ));
+    }
+    lines
+}
+
+#[derive(Default)]
+struct Node<'a> {
+    record: Option<&'a VacuumRecord>,
+    children: BTreeMap<&'a str, Node<'a>>,
+}
+
+fn render_tree(records: &[VacuumRecord], style: &HumanStyle) -> Vec<String> {
+    let mut roots = BTreeMap::<&str, Node<'_>>::new();
+    for record in records {
+        let mut node = roots.entry(record.root.as_str()).or_default();
+        for component in record.relative_path.split('/') {
+            node = node.children.entry(component).or_default();
+        }
+        node.record = Some(record);
+    }
+
+    let mut lines = Vec::new();
+    for (root, node) in &roots {
+        lines.push(printable(root).into_owned());
+        render_children(node, "", style, &mut lines);
+    }
+    lines
+}
+
+fn render_children(node: &Node<'_>, prefix: &str, style: &HumanStyle, lines: &mut Vec<String>) {
+    let last_index = node.children.len().saturating_sub(1);
+    for (index, (name, child)) in node.children.iter().enumerate() {
+        let (branch, indent) = if index == last_index {
+            ("└── ", "    ")
+        } else {
+            ("├── ", "│   ")
+        };
+        let is_dir = !child.children.is_empty()
+            || child
+                .record
+                .is_some_and(|record| record.file_type.as_deref() == Some("dir"));
+        let name = printable(name);
+        let mut line = format!("{prefix}{branch}{name}{}", if is_dir { "/" } else { "" });
+        if let Some(record) = child.record {
+            if record._skipped == Some(true) {
+                line.push_str("  skipped");
+            } else if !is_dir {
+                let details = [
+                    record.size.map(human_size),
+                    record
+                        .mtime
+                        .as_deref()
+                        .and_then(|mtime| relative_time(mtime, style.now)),
+                ];
+                for detail in details.into_iter().flatten() {
+                    line.push_str("  ");
+                    line.push_str(&detail);
+                }
+            }
+            line.push_str(&warning_suffix(record));
+            line = highlight(line, record, style);
+        }
+        lines.push(line);
+        render_children(child, &format!("{prefix}{indent}"), style, lines);
+    }
+}
+
+/// `  ! CODE: message` for the first warning, plus a count of any others.
+fn warning_suffix(record: &VacuumRecord) -> String {
+    let warnings = record._warnings.as_deref().unwrap_or_default();
+    let Some(first) = warnings.first() else {
+        return String::new();
+    };
+    let more = match warnings.len() {
+        1 => String::new(),
+        count => format!(" (+{} more)", count - 1),
+    };
+    format!(
+        "  ! {}: {}{more}",
+        printable(&first.code),
+        printable(&first.message)
+    )
+}
+
+/// Paths and messages come from the filesystem, so control characters (C0,
+/// DEL, C1) are shown escaped, as in `\n` or `\u{1b}`, rather than sent to the
+/// terminal where they could move the cursor or split a row.
+fn printable(value: &str) -> Cow<'_, str> {
+    if !value.chars().any(char::is_control) {
+        return Cow::Borrowed(value);
+    }
+    Cow::Owned(
+        value
+            .chars()
+            .map(|character| {
+                if character.is_control() {
+                    character.escape_debug().to_string()
+                } else {
+                    character.to_string()
+                }
+            })
+            .collect(),
+    )
+}
+
+/// Skipped records are red and records with warnings yellow, when color is on.
+fn highlight(line: String, record: &VacuumRecord, style: &HumanStyle) -> String {
+    if !style.color {
+        return line;
+    }
+    if record._skipped == Some(true) {
+        format!("{RED}{line}{RESET}")
+    } else if record._warnings.is_some() {
+        format!("{YELLOW}{line}{RESET}")
+    } else {
+        line
+    }
+}
+
+fn footer(summary: &Summary, style: &HumanStyle) -> String {
+    let mut footer = format!(
+        "{}, {} ({} bytes)",
+        plural(summary.tally.files, "file"),
+        human_size(summary.tally.bytes),
+        summary.tally.bytes
+    );
+    if summary.dirs > 0 {
+        footer.push_str(&format!(", {}", plural(summary.dirs, "dir")));
+    }
+    if summary.tally.skipped > 0 {
+        let codes = summary
+            .skipped_by_code
+            .iter()
+            .map(|(code, count)| format!("{}: {count}", printable(code)))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let skipped = format!(", {} skipped ({codes})", summary.tally.skipped);
+        footer.push_str(&if style.color {
+            format!("{RED}{skipped}{RESET}")
+        } else {
+            skipped
+        });
+    }
+    let range = [&summary.mtime.oldest, &summary.mtime.newest].map(|mtime| {
+        mtime
+            .as_deref()
+            .and_then(|mtime| relative_time(mtime, style.now))
+    });
+    if let [Some(oldest), Some(newest)] = range {
+        footer.push_str(&format!("; modified {oldest} to {newest}"));
+    }
+    footer
+}
+
+fn plural(count: u64, noun: &str) -> String {
+    if count == 1 {
+        format!("1 {noun}")
+    } else {
+        format!("{count} {noun}s")
+    }
+}
+
+/// Binary units with one decimal above 1 KiB, e.g. `512 B`, `1.5 KiB`.
+fn human_size(bytes: u64) -> String {
+    const UNITS: [&str; 6] = ["KiB", "MiB", "GiB", "TiB", "PiB", "EiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    // Display only; f64 precision is ample for one decimal.
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{value:.1} {}", UNITS[unit])
+}
+
+/// Coarse age of an RFC 3339 timestamp relative to `now`: `45s ago`, `3h ago`,
+/// `12d ago`, `4mo ago`, `2y ago`, or `in 5m` for times in the future.
+fn relative_time(mtime: &str, now: DateTime<Utc>) -> Option<String> {
+    let time = DateTime::parse_from_rfc3339(mtime)
+        .ok()?
+        .with_timezone(&Utc);
+    let seconds = (now - time).num_seconds();
+    let magnitude = seconds.unsigned_abs();
+    let amount = match magnitude {
+        0..60 => format!("{magnitude}s"),
+        60..3_600 => format!("{}m", magnitude / 60),
+        3_600..172_800 => format!("{}h", magnitude / 3_600),
+        172_800..5_184_000 => format!("{}d", magnitude / 86_400),
+        5_184_000..63_072_000 => format!("{}mo", magnitude / 2_592_000),
+        _ => format!("{}y", magnitude / 31_536_000),
+    };
+    Some(if seconds < 0 {
+        format!("in {amount}")
+    } else {
+        format!("{amount} ago")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Utc};
+    use serde_json::json;
+
+    use super::{HumanLayout, HumanStyle, human_size, printable, relative_time, write_human};
+    use crate::record::builder::{VacuumRecord, Warning};
+
+    fn now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2026-01-10T00:00:00Z")
+            .expect("timestamp parses")
+            .with_timezone(&Utc)
+    }
+
+    fn render(layout: HumanLayout) -> String {
+        let mut file = VacuumRecord::empty();
+        file.relative_path = "q1/a.csv".to_string();
+        file.root = "/data".to_string();
+        file.file_type = Some("file".to_string());
+        file.size = Some(1536);
+        file.mtime = Some("2026-01-07T00:00:00.000Z".to_string());
+        let mut skipped = VacuumRecord::empty();
+        skipped.relative_path = "q1/locked.csv".to_string();
+        skipped.root = "/data".to_string();
+        skipped._skipped = Some(true);
+        skipped._warnings = Some(vec![Warning {
+            tool: "vacuum".to_string(),
+            code: "E_IO".to_string(),
+            message: "Permission denied".to_string(),
+            detail: json!({}),
+        }]);
+        let mut top = VacuumRecord::empty();
+        top.relative_path = "top.txt".to_string();
+        top.root = "/data".to_string();
+        top.file_type = Some("file".to_string());
+        top.size = Some(12);
+        top.mtime = Some("2026-01-09T21:00:00.000Z".to_string());
+
+        let records = [file, skipped, top].into_iter().map(Ok);
+        let style = HumanStyle {
+            color: false,
+            now: now(),
+        };
+        let mut written = Vec::new();
+        write_human(
+            records,
+            layout,
+            &style,
+            &mut written,
+            &mut blake3::Hasher::new(),
+        )
+        .expect("human output should be written");
+        String::from_utf8(written).expect("output is utf-8")
+    }
+
+    #[test]
+    fn table_aligns_columns_and_ends_with_totals() {
+        assert_eq!(
+            render(HumanLayout::Table),
+            "      SIZE  MODIFIED    TYPE     PATH\n\
+             \x20  1.5 KiB  3d ago      file     q1/a.csv\n\
+             \x20        -  -           skipped  q1/locked.csv  ! E_IO: Permission denied\n\
+             \x20     12 B  3h ago      file     top.txt\n\
+             \n\
+             2 files, 1.5 KiB (1548 bytes), 1 skipped (E_IO: 1); modified 3d ago to 3h ago\n"
+        );
+    }
+
+    #[test]
+    fn tree_nests_records_under_their_directories() {
+        assert_eq!(
+            render(HumanLayout::Tree),
+            "/data\n\
+             ├── q1/\n\
+             │   ├── a.csv  1.5 KiB  3d ago\n\
+             │   └── locked.csv  skipped  ! E_IO: Permission denied\n\
+             └── top.txt  12 B  3h ago\n\
+             \n\
+             2 files, 1.5 KiB (1548 bytes), 1 skipped (E_IO: 1); modified 3d ago to 3h ago\n"
+        );
+    }
+
+    #[test]
+    fn control_characters_in_names_are_escaped() {
+        let mut record = VacuumRecord::empty();
+        record.relative_path = "evil\x1b[2Jname\nsecond.txt".to_string();
+        record.root = "/data".to_string();
+        record.file_type = Some("file".to_string());
+        record.size = Some(1);
+        let style = HumanStyle {
+            color: false,
+            now: now(),
+        };
+        let mut written = Vec::new();
+        write_human(
+            std::iter::once(Ok(record)),
+            HumanLayout::Table,
+            &style,
+            &mut written,
+            &mut blake3::Hasher::new(),
+        )
+        .expect("human output should be written");
+        let written = String::from_utf8(written).expect("output is utf-8");
+
+        assert!(!written.contains('\x1b'));
+        assert_eq!(
+            written.lines().nth(1),
+            Some("       1 B  -           file     evil\\u{1b}[2Jname\\nsecond.txt")
+        );
+        assert_eq!(printable("tab\there"), "tab\\there");
+        assert_eq!(printable("café"), "café");
+    }
+
+    #[test]
+    fn sizes_and_ages_use_coarse_units() {
+        assert_eq!(human_size(0), "0 B");
+        assert_eq!(human_size(1024 * 1024 * 3 / 2), "1.5 MiB");
+        assert_eq!(
+            relative_time("2026-01-09T23:59:15.000Z", now()).as_deref(),
+            Some("45s ago")
+        );
+        assert_eq!(
+            relative_time("2025-10-01T00:00:00.000Z", now()).as_deref(),
+            Some("3mo ago")
+        );
+        assert_eq!(
+            relative_time("2026-01-10T00:05:00.000Z", now()).as_deref(),
+            Some("in 5m")
+        );
+    }
+}
@@ -1,4 +1,6 @@
-use crate::record::builder::VacuumRecord;
+use std::io::{self, Write};
+
+use crate::{output::sort::compare_records, record::builder::VacuumRecord};
 
 pub fn emit_records(records: &[VacuumRecord]) {
     for line in serialize_sorted_jsonl(records) {
@@ -29,13 +31,62 @@ pub(crate) fn serialize_sorted_jsonl(records: &[VacuumRecord]) -> Vec<String> {
         .collect()
 }
 
+/// Writes each line followed by `\n`, feeding exactly the bytes written into
+/// `hasher` so a failed write still hashes what reached the writer.
+pub(crate) fn write_lines<I, W>(
+    lines: I,
+    writer: &mut W,
+    hasher: &mut blake3::Hasher,
+) -> io::Result<()>
+where
+    I: IntoIterator<Item = io::Result<String>>,
+    W: Write,
+{
+    write_terminated(lines, b"\n", writer, hasher)
+}
+
+/// Writes each record as one JSONL line through [`write_lines`].
+pub(crate) fn write_records<I, W>(
+    records: I,
+    writer: &mut W,
+    hasher: &mut blake3::Hasher,
+) -> io::Result<()>
+where
+    I: IntoIterator<Item = io::Result<VacuumRecord>>,
+    W: Write,
+{
+    let lines = records
+        .into_iter()
+        .map(|record| serde_json::to_string(&record?).map_err(io::Error::other));
+    write_lines(lines, writer, hasher)
+}
+
+/// [`write_lines`] with a caller-chosen line terminator. Every scan output
+/// format writes through here, so the witness `output_hash` always covers
+/// exactly the bytes emitted, whatever `--format` or `--human` chose.
+pub(crate) fn write_terminated<I, W>(
+    lines: I,
+    terminator: &[u8],
+    writer: &mut W,
+    hasher: &mut blake3::Hasher,
+) -> io::Result<()>
+where
+    I: IntoIterator<Item = io::Result<String>>,
+    W: Write,
+{
+    for line in lines {
+        let line = line?;
+        writer.write_all(line.as_bytes())?;
+        writer.write_all(terminator)?;
+        hasher.update(line.as_bytes());
+        hasher.update(terminator);
+    }
+    writer.flush()
+}
+
 fn sorted_records(records: &[VacuumRecord]) -> Vec<VacuumRecord> {
     let mut sorted = records.to_vec();
-    sorted.sort_by(|left, right| {
-        left.relative_path
-            .cmp(&right.relative_path)
-            .then_with(|| left.root.cmp(&right.root))
-    });
+    sorted.sort_by(compare_records);
     sorted
 }
 
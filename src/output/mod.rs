@@ -1 +1,32 @@
+use clap::ValueEnum;
+
+pub mod csv;
+pub mod human;
 pub mod jsonl;
+pub mod mtree;
+pub mod sort;
+
+/// Scan output encoding selected with `--format`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum OutputFormat {
+    /// One `vacuum.v0` JSON record per line.
+    #[default]
+    Jsonl,
+    /// RFC 4180 comma-separated values with a header row and CRLF line ends.
+    Csv,
+    /// Tab-separated values with a header row and backslash-escaped fields.
+    Tsv,
+    /// An mtree(5) specification of a single root.
+    Mtree,
+}
+
+impl OutputFormat {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Jsonl => "jsonl",
+            Self::Csv => "csv",
+            Self::Tsv => "tsv",
+            Self::Mtree => "mtree",
+        }
+    }
+}
@@ -0,0 +1,155 @@
+use std::io::{self, Write};
+
+use chrono::{DateTime, Utc};
+
+use crate::{output::jsonl::write_lines, record::builder::VacuumRecord};
+
+/// Writes the sorted records as an mtree(5) specification. Entries use
+/// full-path form (`./relative/path`), so the spec needs no `/set` state and
+/// every line stands alone.
+pub(crate) fn write_spec<I, W>(
+    records: I,
+    writer: &mut W,
+    hasher: &mut blake3::Hasher,
+) -> io::Result<()>
+where
+    I: IntoIterator<Item = io::Result<VacuumRecord>>,
+    W: Write,
+{
+    let entries = records.into_iter().map(|record| Ok(render_entry(&record?)));
+    write_lines(
+        std::iter::once(Ok("#mtree".to_string())).chain(entries),
+        writer,
+        hasher,
+    )
+}
+
+/// One entry with keywords in a fixed order: `type`, `size`, `time`, `mode`,
+/// `uid`, `gid`, `uname`, `gname`, `link`. Absent fields are left out. mtree
+/// has no notion of an unreadable entry, so a `_skipped` record becomes a
+/// comment naming its path and warning codes.
+fn render_entry(record: &VacuumRecord) -> String {
+    let name = format!("./{}", escape(&record.relative_path));
+    if record._skipped == Some(true) {
+        let codes = record
+            ._warnings
+            .iter()
+            .flatten()
+            .map(|warning| warning.code.as_str())
+            .collect::<Vec<_>>();
+        return format!("# skipped {name} {}", codes.join(";"))
+            .trim_end()
+            .to_string();
+    }
+
+    let mut entry = name;
+    let mut keyword = |key: &str, value: &str| {
+        entry.push(' ');
+        entry.push_str(key);
+        entry.push('=');
+        entry.push_str(value);
+    };
+    if let Some(kind) = record.file_type.as_deref() {
+        keyword("type", mtree_type(kind));
+    }
+    if let Some(size) = record.size {
+        keyword("size", &size.to_string());
+    }
+    if let Some(time) = record.mtime.as_deref().and_then(mtree_time) {
+        keyword("time", &time);
+    }
+    if let Some(mode) = record.mode.as_deref() {
+        keyword("mode", mode);
+    }
+    if let Some(uid) = record.uid {
+        keyword("uid", &uid.to_string());
+    }
+    if let Some(gid) = record.gid {
+        keyword("gid", &gid.to_string());
+    }
+    if let Some(user) = record.user.as_deref() {
+        keyword("uname", &escape(user));
+    }
+    if let Some(group) = record.group.as_deref() {
+        keyword("gname", &escape(group));
+    }
+    if record.file_type.as_deref() == Some("symlink")
+        && let Some(symlink) = record.symlink.as_ref()
+    {
+        keyword("link", &escape(&symlink.target));
+    }
+    entry
+}
+
+fn mtree_type(file_type: &str) -> &str {
+    match file_type {
+        "symlink" => "link",
+        other => other,
+    }
+}
+
+/// mtree times are `seconds.nanoseconds` since the epoch.
+fn mtree_time(mtime: &str) -> Option<String> {
+    let time = DateTime::parse_from_rfc3339(mtime)
+        .ok()?
+        .with_timezone(&Utc);
+    Some(format!(
+        "{}.{:09}",
+        time.timestamp(),
+        time.timestamp_subsec_nanos()
+    ))
+}
+
+/// Octal-escapes every byte mtree readers treat specially: whitespace and
+/// control bytes, non-ASCII bytes, `#`, `=`, and `\`.
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte <= b' ' || byte >= 0x7f || matches!(byte, b'#' | b'=' | b'\\') {
+            escaped.push_str(&format!("\\{byte:03o}"));
+        } else {
+            escaped.push(char::from(byte));
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{escape, render_entry};
+    use crate::record::builder::{SymlinkInfo, VacuumRecord};
+
+    #[test]
+    fn entries_render_keywords_in_fixed_order() {
+        let mut record = VacuumRecord::empty();
+        record.relative_path = "q1/tape 1.csv".to_string();
+        record.file_type = Some("file".to_string());
+        record.size = Some(42);
+        record.mtime = Some("2026-01-01T00:00:00.250Z".to_string());
+        record.mode = Some("0644".to_string());
+        record.uid = Some(1000);
+        assert_eq!(
+            render_entry(&record),
+            "./q1/tape\\0401.csv type=file size=42 time=1767225600.250000000 mode=0644 uid=1000"
+        );
+
+        let mut link = VacuumRecord::empty();
+        link.relative_path = "latest".to_string();
+        link.file_type = Some("symlink".to_string());
+        link.symlink = Some(SymlinkInfo {
+            target: "q1/tape 1.csv".to_string(),
+            resolved: None,
+            escapes_root: false,
+        });
+        assert_eq!(
+            render_entry(&link),
+            "./latest type=link link=q1/tape\\0401.csv"
+        );
+    }
+
+    #[test]
+    fn special_bytes_are_octal_escaped() {
+        assert_eq!(escape("a#b=c\\d"), "a\\043b\\075c\\134d");
+        assert_eq!(escape("é"), "\\303\\251");
+    }
+}
@@ -0,0 +1,321 @@
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::BinaryHeap,
+    fs::{self, File, OpenOptions},
+    io::{self, BufRead, BufReader, BufWriter, Lines, Write},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::record::builder::VacuumRecord;
+
+/// Maximum number of runs merged at once; more runs are merged in passes.
+const MAX_MERGE_FAN_IN: usize = 64;
+
+pub fn compare_records(left: &VacuumRecord, right: &VacuumRecord) -> Ordering {
+    left.relative_path
+        .cmp(&right.relative_path)
+        .then_with(|| left.root.cmp(&right.root))
+}
+
+/// Sorts records by `(relative_path, root)` with bounded memory. Records stay
+/// in memory until `buffer_limit` is reached; after that each full buffer is
+/// written to the spill directory as a sorted JSONL run and the runs are
+/// merge-sorted on `finish`.
+pub struct ExternalSorter {
+    buffer: Vec<VacuumRecord>,
+    buffer_limit: usize,
+    spill_parent: PathBuf,
+    spill: Option<SpillDir>,
+    runs: Vec<PathBuf>,
+}
+
+impl ExternalSorter {
+    pub fn new(buffer_limit: usize, spill_parent: PathBuf) -> Self {
+        Self {
+            buffer: Vec::new(),
+            buffer_limit: buffer_limit.max(1),
+            spill_parent,
+            spill: None,
+            runs: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, record: VacuumRecord) -> io::Result<()> {
+        self.buffer.push(record);
+        if self.buffer.len() >= self.buffer_limit {
+            self.spill_buffer()?;
+        }
+        Ok(())
+    }
+
+    pub fn spilled(&self) -> bool {
+        !self.runs.is_empty()
+    }
+
+    /// Returns the records in sorted order. Records with equal keys keep the
+    /// order they were pushed in, whatever the buffer limit.
+    pub fn finish(mut self) -> io::Result<SortedRecords> {
+        if self.runs.is_empty() {
+            self.buffer.sort_by(compare_records);
+            return Ok(SortedRecords::Memory(self.buffer.into_iter()));
+        }
+
+        if !self.buffer.is_empty() {
+            self.spill_buffer()?;
+        }
+
+        let mut spill = self
+            .spill
+            .take()
+            .ok_or_else(|| io::Error::other("spill directory missing"))?;
+        let mut runs = self.runs;
+        while runs.len() > MAX_MERGE_FAN_IN {
+            let batch = runs.drain(..MAX_MERGE_FAN_IN).collect::<Vec<_>>();
+            let merged = spill.next_run_path();
+            let mut writer = BufWriter::new(create_run(&merged)?);
+            for record in RunMerger::open(&batch)? {
+                writeln!(writer, "{}", encode(&record?)?)?;
+            }
+            writer.flush()?;
+            for path in &batch {
+                fs::remove_file(path)?;
+            }
+            // The merger breaks ties by run order, so the merged run takes the
+            // place of the runs it replaces.
+            runs.insert(0, merged);
+        }
+
+        Ok(SortedRecords::Merged {
+            merger: RunMerger::open(&runs)?,
+            _spill: spill,
+        })
+    }
+
+    fn spill_buffer(&mut self) -> io::Result<()> {
+        if self.spill.is_none() {
+            self.spill = Some(SpillDir::create(&self.spill_parent)?);
+        }
+        let Some(spill) = self.spill.as_mut() else {
+            return Ok(());
+        };
+
+        self.buffer.sort_by(compare_records);
+        let path = spill.next_run_path();
+        let mut writer = BufWriter::new(create_run(&path)?);
+        for record in self.buffer.drain(..) {
+            writeln!(writer, "{}", encode(&record)?)?;
+        }
+        writer.flush()?;
+        self.runs.push(path);
+        Ok(())
+    }
+}
+
+/// Sorted records, decoded once from their run when spilled, so every output
+/// format renders them without another JSON round trip.
+pub enum SortedRecords {
+    Memory(std::vec::IntoIter<VacuumRecord>),
+    Merged { merger: RunMerger, _spill: SpillDir },
+}
+
+impl Iterator for SortedRecords {
+    type Item = io::Result<VacuumRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Self::Memory(records) => records.next().map(Ok),
+            Self::Merged { merger, .. } => merger.next(),
+        }
+    }
+}
+
+/// K-way merge over sorted JSONL runs, keyed on `(relative_path, root)`. Equal
+/// keys come out in run order.
+pub struct RunMerger {
+    readers: Vec<Lines<BufReader<File>>>,
+    heads: Vec<Option<VacuumRecord>>,
+    heap: BinaryHeap<Reverse<(SortKey, usize)>>,
+}
+
+impl RunMerger {
+    fn open(paths: &[PathBuf]) -> io::Result<Self> {
+        let mut merger = Self {
+            readers: Vec::with_capacity(paths.len()),
+            heads: Vec::with_capacity(paths.len()),
+            heap: BinaryHeap::with_capacity(paths.len()),
+        };
+        for (index, path) in paths.iter().enumerate() {
+            merger
+                .readers
+                .push(BufReader::new(File::open(path)?).lines());
+            merger.heads.push(None);
+            merger.refill(index)?;
+        }
+        Ok(merger)
+    }
+
+    fn refill(&mut self, index: usize) -> io::Result<()> {
+        if let Some(line) = self.readers[index].next() {
+            let record = serde_json::from_str::<VacuumRecord>(&line?).map_err(io::Error::other)?;
+            let key = SortKey {
+                relative_path: record.relative_path.clone(),
+                root: record.root.clone(),
+            };
+            self.heads[index] = Some(record);
+            self.heap.push(Reverse((key, index)));
+        }
+        Ok(())
+    }
+}
+
+impl Iterator for RunMerger {
+    type Item = io::Result<VacuumRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Reverse((_, index)) = self.heap.pop()?;
+        let record = self.heads[index].take()?;
+        match self.refill(index) {
+            Ok(()) => Some(Ok(record)),
+            Err(error) => Some(Err(error)),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct SortKey {
+    relative_path: String,
+    root: String,
+}
+
+/// Scratch directory for sorted runs, removed when dropped.
+pub struct SpillDir {
+    path: PathBuf,
+    next_run: usize,
+}
+
+impl SpillDir {
+    fn create(parent: &Path) -> io::Result<Self> {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_nanos())
+            .unwrap_or_default();
+        let path = parent.join(format!("vacuum-sort-{}-{nanos}", std::process::id()));
+        fs::create_dir_all(parent)?;
+        fs::create_dir(&path)?;
+        Ok(Self { path, next_run: 0 })
+    }
+
+    fn next_run_path(&mut self) -> PathBuf {
+        let index = self.next_run;
+        self.next_run += 1;
+        self.path.join(format!("run-{index:06}.jsonl"))
+    }
+}
+
+impl Drop for SpillDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+fn create_run(path: &Path) -> io::Result<File> {
+    OpenOptions::new().write(true).create_new(true).open(path)
+}
+
+fn encode(record: &VacuumRecord) -> io::Result<String> {
+    serde_json::to_string(record).map_err(io::Error::other)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::{output::jsonl::serialize_sorted_jsonl, record::builder::VacuumRecord};
+
+    use super::{ExternalSorter, MAX_MERGE_FAN_IN, encode};
+
+    fn record(relative_path: &str, root: &str) -> VacuumRecord {
+        let mut record = VacuumRecord::empty();
+        record.path = format!("{root}/{relative_path}");
+        record.relative_path = relative_path.to_string();
+        record.root = root.to_string();
+        record.size = Some(relative_path.len() as u64);
+        record
+    }
+
+    fn shuffled_records(count: usize) -> Vec<VacuumRecord> {
+        (0..count)
+            .map(|index| {
+                let spread = (index * 7919) % count;
+                record(
+                    &format!("dir-{}/file-{spread}.csv", spread % 5),
+                    &format!("/root-{}", index % 3),
+                )
+            })
+            .collect()
+    }
+
+    fn sorted_lines(records: Vec<VacuumRecord>, buffer_limit: usize) -> (Vec<String>, bool) {
+        let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+        let mut sorter = ExternalSorter::new(buffer_limit, temp_dir.path().to_path_buf());
+        for record in records {
+            sorter.push(record).expect("push should succeed");
+        }
+        let spilled = sorter.spilled();
+        let lines = sorter
+            .finish()
+            .expect("finish should succeed")
+            .map(|record| encode(&record?))
+            .collect::<Result<Vec<_>, _>>()
+            .expect("merge should succeed");
+
+        let leftovers = fs::read_dir(temp_dir.path())
+            .expect("spill parent should be readable")
+            .count();
+        assert_eq!(leftovers, 0, "spill directory should be removed");
+        (lines, spilled)
+    }
+
+    #[test]
+    fn in_memory_sort_matches_collect_then_sort() {
+        let records = shuffled_records(50);
+        let (lines, spilled) = sorted_lines(records.clone(), 1000);
+
+        assert!(!spilled);
+        assert_eq!(lines, serialize_sorted_jsonl(&records));
+    }
+
+    #[test]
+    fn spilled_runs_merge_into_collect_then_sort_order() {
+        let records = shuffled_records(97);
+        let (lines, spilled) = sorted_lines(records.clone(), 10);
+
+        assert!(spilled);
+        assert_eq!(lines, serialize_sorted_jsonl(&records));
+    }
+
+    #[test]
+    fn more_runs_than_fan_in_are_merged_in_passes() {
+        let records = shuffled_records(300);
+        let (lines, spilled) = sorted_lines(records.clone(), 2);
+
+        assert!(spilled);
+        assert_eq!(lines, serialize_sorted_jsonl(&records));
+    }
+
+    #[test]
+    fn tied_keys_keep_push_order_across_merge_passes() {
+        let records = (0..MAX_MERGE_FAN_IN * 3 / 2)
+            .map(|index| {
+                let mut record = record(&format!("file-{}.csv", index % 4), "/root");
+                record.size = Some(index as u64);
+                record
+            })
+            .collect::<Vec<_>>();
+        let (lines, spilled) = sorted_lines(records.clone(), 1);
+
+        assert!(spilled);
+        assert_eq!(lines, serialize_sorted_jsonl(&records));
+    }
+}
@@ -1,18 +1,67 @@
 use std::collections::BTreeMap;
 
-use serde::Serialize;
+use serde::{Deserialize, Deserializer, Serialize};
 use serde_json::Value;
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub const RECORD_VERSION: &str = "vacuum.v0";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct VacuumRecord {
+    /// Always [`RECORD_VERSION`]; readers check the raw value before
+    /// deserializing (see `record::manifest`).
+    #[serde(skip_deserializing, default = "record_version")]
     pub version: &'static str,
     pub path: String,
     pub relative_path: String,
     pub root: String,
     pub size: Option<u64>,
     pub mtime: Option<String>,
+    /// `--times` fields. `btime` is `Some(None)` (serialized as `null`) when
+    /// the filesystem does not report a birth time.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ctime: Option<String>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "present"
+    )]
+    pub btime: Option<Option<String>>,
+    /// Only with `--atime`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub atime: Option<String>,
     pub extension: Option<String>,
     pub mime_guess: Option<String>,
+    /// `file`, `dir`, `symlink`, `fifo`, `socket`, `block`, or `char`; `null`
+    /// when the entry could not be stat'd. Defaults to `null` when reading
+    /// manifests written before the field existed.
+    #[serde(default)]
+    pub file_type: Option<String>,
+    /// Present only when the entry itself is a symlink.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symlink: Option<SymlinkInfo>,
+    /// Magic-byte detection result; only present under `--sniff` when a
+    /// signature matched.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mime_sniffed: Option<String>,
+    /// `--posix` fields: permission bits as an octal string, numeric owner,
+    /// and the inode identity used to spot hard links.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uid: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gid: Option<u32>,
+    /// Owner names; only with `--owner-names` when the id resolves.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inode: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dev: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nlink: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub _skipped: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -20,7 +69,17 @@ pub struct VacuumRecord {
     pub tool_versions: BTreeMap<String, String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SymlinkInfo {
+    /// Raw `readlink` value, exactly as stored in the link.
+    pub target: String,
+    /// Canonical path the link resolves to; `null` when it dangles.
+    pub resolved: Option<String>,
+    /// The target lies outside the record's `root`.
+    pub escapes_root: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Warning {
     pub tool: String,
     pub code: String,
@@ -34,17 +93,44 @@ impl VacuumRecord {
         tool_versions.insert("vacuum".to_string(), env!("CARGO_PKG_VERSION").to_string());
 
         Self {
-            version: "vacuum.v0",
+            version: RECORD_VERSION,
             path: String::new(),
             relative_path: String::new(),
             root: String::new(),
             size: None,
             mtime: None,
+            ctime: None,
+            btime: None,
+            atime: None,
             extension: None,
             mime_guess: None,
+            file_type: None,
+            symlink: None,
+            mime_sniffed: None,
+            mode: None,
+            uid: None,
+            gid: None,
+            user: None,
+            group: None,
+            inode: None,
+            dev: None,
+            nlink: None,
             _skipped: None,
             _warnings: None,
             tool_versions,
         }
     }
 }
+
+/// Keeps an explicit `null` distinct from an absent field.
+fn present<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+fn record_version() -> &'static str {
+    RECORD_VERSION
+}
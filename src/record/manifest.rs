@@ -0,0 +1,168 @@
+use std::{
+    fs::File,
+    io::{BufRead, BufReader},
+    path::Path,
+};
+
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::{
+    record::builder::{RECORD_VERSION, VacuumRecord},
+    refusal::{codes::RefusalCode, payload::Refusal},
+};
+
+/// Reads a `vacuum.v0` JSONL manifest written by a previous scan. Blank lines
+/// are ignored; any other line that is not a record refuses with the 1-based
+/// line number.
+pub fn read_manifest(path: &Path) -> Result<Vec<VacuumRecord>, Refusal> {
+    let file = File::open(path).map_err(|error| {
+        Refusal::new(
+            RefusalCode::Io,
+            json!({
+                "manifest": path.display().to_string(),
+                "error": error.to_string(),
+            }),
+        )
+    })?;
+    parse_lines(path, BufReader::new(file))
+}
+
+/// Parses manifest bytes that are already in memory, so a caller that hashes
+/// the manifest parses exactly the bytes it hashed. `path` only labels
+/// refusals.
+pub fn parse_manifest(path: &Path, bytes: &[u8]) -> Result<Vec<VacuumRecord>, Refusal> {
+    parse_lines(path, bytes)
+}
+
+fn parse_lines(path: &Path, reader: impl BufRead) -> Result<Vec<VacuumRecord>, Refusal> {
+    let mut records = Vec::new();
+    for (index, line) in reader.lines().enumerate() {
+        let line = line.map_err(|error| bad_manifest(path, index + 1, &error.to_string()))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record = parse_record(&line).map_err(|error| bad_manifest(path, index + 1, &error))?;
+        records.push(record);
+    }
+    Ok(records)
+}
+
+#[derive(Deserialize)]
+struct VersionProbe<'a> {
+    #[serde(borrow)]
+    version: Option<&'a str>,
+}
+
+fn parse_record(line: &str) -> Result<VacuumRecord, String> {
+    let probe =
+        serde_json::from_str::<VersionProbe<'_>>(line).map_err(|error| error.to_string())?;
+    if probe.version != Some(RECORD_VERSION) {
+        return Err(format!(
+            "expected version {RECORD_VERSION:?}, found {:?}",
+            probe.version.unwrap_or("none")
+        ));
+    }
+    serde_json::from_str(line).map_err(|error| error.to_string())
+}
+
+pub(crate) fn bad_manifest(path: &Path, line: usize, error: &str) -> Refusal {
+    Refusal::new(
+        RefusalCode::BadManifest,
+        json!({
+            "manifest": path.display().to_string(),
+            "line": line,
+            "error": error,
+        }),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::{output::jsonl::serialize_sorted_jsonl, record::builder::VacuumRecord};
+
+    use super::{parse_manifest, read_manifest};
+    use crate::refusal::codes::RefusalCode;
+
+    fn record(relative_path: &str) -> VacuumRecord {
+        let mut record = VacuumRecord::empty();
+        record.path = format!("/data/{relative_path}");
+        record.relative_path = relative_path.to_string();
+        record.root = "/data".to_string();
+        record.size = Some(3);
+        record.mtime = Some("2026-01-01T00:00:00.000Z".to_string());
+        record
+    }
+
+    #[test]
+    fn manifest_round_trips_through_record_types() {
+        let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+        let path = temp_dir.path().join("manifest.jsonl");
+        let records = vec![record("a.csv"), record("b.csv")];
+        fs::write(&path, serialize_sorted_jsonl(&records).join("\n") + "\n\n")
+            .expect("manifest should be written");
+
+        assert_eq!(
+            read_manifest(&path).expect("manifest should parse"),
+            records
+        );
+    }
+
+    #[test]
+    fn bytes_in_memory_parse_like_the_file() {
+        let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+        let path = temp_dir.path().join("manifest.jsonl");
+        let bytes = serialize_sorted_jsonl(&[record("a.csv"), record("b.csv")]).join("\n") + "\n";
+        fs::write(&path, &bytes).expect("manifest should be written");
+
+        assert_eq!(
+            parse_manifest(&path, bytes.as_bytes()).expect("bytes should parse"),
+            read_manifest(&path).expect("manifest should parse")
+        );
+    }
+
+    #[test]
+    fn unsupported_birth_time_stays_null_after_round_trip() {
+        let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+        let path = temp_dir.path().join("manifest.jsonl");
+        let mut unsupported = record("a.csv");
+        unsupported.ctime = Some("2026-01-02T00:00:00.000Z".to_string());
+        unsupported.btime = Some(None);
+        let lines = serialize_sorted_jsonl(&[unsupported.clone(), record("b.csv")]);
+        assert!(lines[0].contains("\"btime\":null"));
+        assert!(!lines[1].contains("btime"));
+        fs::write(&path, lines.join("\n")).expect("manifest should be written");
+
+        let records = read_manifest(&path).expect("manifest should parse");
+
+        assert_eq!(records[0], unsupported);
+        assert_eq!(records[1].btime, None);
+    }
+
+    #[test]
+    fn foreign_lines_refuse_with_line_number() {
+        let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+        let path = temp_dir.path().join("manifest.jsonl");
+        let valid = serialize_sorted_jsonl(&[record("a.csv")]).join("");
+        fs::write(
+            &path,
+            format!("{valid}\n{}\n", valid.replace("vacuum.v0", "vacuum.v9")),
+        )
+        .expect("manifest should be written");
+
+        let refusal = read_manifest(&path).expect_err("wrong version should refuse");
+        assert_eq!(refusal.code, RefusalCode::BadManifest);
+        assert_eq!(refusal.detail["line"], 2);
+    }
+
+    #[test]
+    fn missing_manifest_refuses_with_io_code() {
+        let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+        let refusal = read_manifest(&temp_dir.path().join("missing.jsonl"))
+            .expect_err("missing manifest should refuse");
+
+        assert_eq!(refusal.code, RefusalCode::Io);
+    }
+}
@@ -1,3 +1,12 @@
+use std::{
+    fs::File,
+    io::{self, Read},
+    path::Path,
+};
+
+/// Bytes read from the start of a file when `--sniff` is enabled.
+pub const SNIFF_LEN: usize = 512;
+
 pub fn guess_from_extension(extension: Option<&str>) -> Option<&'static str> {
     let extension = extension?;
 
@@ -32,9 +41,83 @@ pub fn guess_from_extension(extension: Option<&str>) -> Option<&'static str> {
     }
 }
 
+/// Reads at most [`SNIFF_LEN`] bytes of `path` and matches them against
+/// [`sniff_bytes`].
+pub fn sniff_file(path: &Path) -> io::Result<Option<&'static str>> {
+    let mut buffer = Vec::with_capacity(SNIFF_LEN);
+    File::open(path)?
+        .take(SNIFF_LEN as u64)
+        .read_to_end(&mut buffer)?;
+    Ok(sniff_bytes(&buffer))
+}
+
+/// Detects a MIME type from leading magic bytes, independent of the file
+/// name. Returns `None` when no known signature matches.
+pub fn sniff_bytes(bytes: &[u8]) -> Option<&'static str> {
+    if bytes.starts_with(b"%PDF-") {
+        Some("application/pdf")
+    } else if bytes.starts_with(b"PK\x03\x04") {
+        Some(sniff_zip_flavor(bytes))
+    } else if bytes.starts_with(b"PK\x05\x06") || bytes.starts_with(b"PK\x07\x08") {
+        Some("application/zip")
+    } else if bytes.starts_with(&[0x1f, 0x8b]) {
+        Some("application/gzip")
+    } else if bytes.starts_with(b"PAR1") {
+        Some("application/vnd.apache.parquet")
+    } else if bytes.starts_with(&[0xd0, 0xcf, 0x11, 0xe0, 0xa1, 0xb1, 0x1a, 0xe1]) {
+        Some("application/x-ole-storage")
+    } else if let Some(text) = bytes.strip_prefix(b"\xef\xbb\xbf") {
+        Some(sniff_markup(text).unwrap_or("text/plain"))
+    } else {
+        sniff_markup(bytes)
+    }
+}
+
+/// OOXML packages are ZIP archives whose part names reveal the document
+/// kind; the first local headers usually fall inside the sniffed prefix.
+fn sniff_zip_flavor(bytes: &[u8]) -> &'static str {
+    if !contains(bytes, b"[Content_Types].xml") && !contains(bytes, b"_rels/") {
+        "application/zip"
+    } else if contains(bytes, b"xl/") {
+        "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"
+    } else if contains(bytes, b"word/") {
+        "application/vnd.openxmlformats-officedocument.wordprocessingml.document"
+    } else if contains(bytes, b"ppt/") {
+        "application/vnd.openxmlformats-officedocument.presentationml.presentation"
+    } else {
+        "application/zip"
+    }
+}
+
+fn sniff_markup(bytes: &[u8]) -> Option<&'static str> {
+    let start = bytes.iter().position(|byte| !byte.is_ascii_whitespace())?;
+    let text = &bytes[start..];
+    if text.starts_with(b"<?xml") {
+        Some("application/xml")
+    } else if starts_with_ignore_case(text, b"<!doctype html")
+        || starts_with_ignore_case(text, b"<html")
+    {
+        Some("text/html")
+    } else {
+        None
+    }
+}
+
+fn starts_with_ignore_case(bytes: &[u8], prefix: &[u8]) -> bool {
+    bytes.len() >= prefix.len() && bytes[..prefix.len()].eq_ignore_ascii_case(prefix)
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack
+        .windows(needle.len())
+        .any(|window| window == needle)
+}
+
 #[cfg(test)]
 mod tests {
-    use super::guess_from_extension;
+    use std::fs;
+
+    use super::{SNIFF_LEN, guess_from_extension, sniff_bytes, sniff_file};
 
     #[test]
     fn maps_minimum_plan_extensions() {
@@ -76,4 +159,74 @@ mod tests {
             Some("application/x-jsonlines")
         );
     }
+
+    #[test]
+    fn sniffs_binary_signatures() {
+        let cases: [(&[u8], &str); 6] = [
+            (b"%PDF-1.7\n", "application/pdf"),
+            (b"PK\x03\x04\x14\x00plain.txt", "application/zip"),
+            (b"PK\x05\x06\x00\x00", "application/zip"),
+            (&[0x1f, 0x8b, 0x08, 0x00], "application/gzip"),
+            (b"PAR1\x15\x04", "application/vnd.apache.parquet"),
+            (
+                &[0xd0, 0xcf, 0x11, 0xe0, 0xa1, 0xb1, 0x1a, 0xe1, 0x00],
+                "application/x-ole-storage",
+            ),
+        ];
+
+        for (bytes, mime) in cases {
+            assert_eq!(sniff_bytes(bytes), Some(mime));
+        }
+    }
+
+    #[test]
+    fn sniffs_ooxml_flavor_from_zip_part_names() {
+        let workbook = b"PK\x03\x04\x14\x00[Content_Types].xml....PK\x03\x04xl/workbook.xml";
+        let document = b"PK\x03\x04\x14\x00[Content_Types].xml....PK\x03\x04word/document.xml";
+
+        assert_eq!(
+            sniff_bytes(workbook),
+            Some("application/vnd.openxmlformats-officedocument.spreadsheetml.sheet")
+        );
+        assert_eq!(
+            sniff_bytes(document),
+            Some("application/vnd.openxmlformats-officedocument.wordprocessingml.document")
+        );
+    }
+
+    #[test]
+    fn sniffs_text_markers() {
+        assert_eq!(
+            sniff_bytes(b"<?xml version=\"1.0\"?><a/>"),
+            Some("application/xml")
+        );
+        assert_eq!(
+            sniff_bytes(b"\xef\xbb\xbf<?xml version=\"1.0\"?>"),
+            Some("application/xml")
+        );
+        assert_eq!(sniff_bytes(b"\xef\xbb\xbfid,name\n"), Some("text/plain"));
+        assert_eq!(
+            sniff_bytes(b"\n  <!DOCTYPE HTML><table>"),
+            Some("text/html")
+        );
+        assert_eq!(sniff_bytes(b"<html><body>"), Some("text/html"));
+    }
+
+    #[test]
+    fn unknown_content_sniffs_to_none() {
+        assert_eq!(sniff_bytes(b""), None);
+        assert_eq!(sniff_bytes(b"id,name\n1,a\n"), None);
+        assert_eq!(sniff_bytes(b"PK"), None);
+    }
+
+    #[test]
+    fn sniff_file_reads_only_the_prefix() {
+        let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+        let path = temp_dir.path().join("report.dat");
+        let mut contents = vec![b' '; SNIFF_LEN];
+        contents.extend_from_slice(b"<?xml version=\"1.0\"?>");
+        fs::write(&path, contents).expect("fixture should be written");
+
+        assert_eq!(sniff_file(&path).expect("file should be readable"), None);
+    }
 }
@@ -1,3 +1,6 @@
 pub mod builder;
+pub mod manifest;
 pub mod mime;
+pub mod mtree;
 pub mod path;
+pub mod posix;
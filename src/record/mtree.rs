@@ -0,0 +1,355 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, SecondsFormat};
+use serde_json::json;
+
+use crate::{
+    cli::exit,
+    output::jsonl::emit_records,
+    record::{
+        builder::{SymlinkInfo, VacuumRecord},
+        mime::guess_from_extension,
+        path::{native_string, normalize_relative},
+        posix::format_mode,
+    },
+    refusal::{
+        codes::RefusalCode,
+        payload::{Refusal, render},
+    },
+    walk::{symlink::lexical_join, walker::absolute_root},
+};
+
+/// `vacuum import-mtree SPEC --root ROOT`: prints the spec's entries as sorted
+/// `vacuum.v0` records under `root`, without reading filesystem metadata.
+pub fn dispatch_import(spec: &Path, root: &Path) -> u8 {
+    match read_mtree(spec, root) {
+        Ok(records) => {
+            emit_records(&records);
+            exit::SCAN_COMPLETE
+        }
+        Err(refusal) => {
+            println!("{}", render(&refusal));
+            exit::REFUSAL
+        }
+    }
+}
+
+/// Reads an mtree(5) specification. Both full-path entries (`./a/b`) and
+/// relative entries (directories descend, `..` ascends) are understood, with
+/// `/set` and `/unset` defaults. Keywords vacuum has no field for, such as
+/// digests, are ignored. A path listed twice keeps its last entry.
+pub fn read_mtree(spec: &Path, root: &Path) -> Result<Vec<VacuumRecord>, Refusal> {
+    let text = fs::read(spec).map_err(|error| {
+        Refusal::new(
+            RefusalCode::Io,
+            json!({
+                "mtree": spec.display().to_string(),
+                "error": error.to_string(),
+            }),
+        )
+    })?;
+    let text = String::from_utf8_lossy(&text);
+    let root = absolute_root(root);
+    let root_value = native_string(&root);
+
+    let mut defaults = BTreeMap::new();
+    let mut current = PathBuf::new();
+    let mut records = BTreeMap::new();
+    for (line_number, line) in logical_lines(&text) {
+        let bad = |error: String| bad_mtree(spec, line_number, &error);
+        let mut tokens = line.split_whitespace();
+        let Some(first) = tokens.next() else {
+            continue;
+        };
+
+        match first {
+            "/set" => {
+                for token in tokens {
+                    let (key, value) = split_keyword(token).map_err(&bad)?;
+                    defaults.insert(key.to_string(), value.to_string());
+                }
+                continue;
+            }
+            "/unset" => {
+                for key in tokens {
+                    if key == "all" {
+                        defaults.clear();
+                    } else {
+                        defaults.remove(key);
+                    }
+                }
+                continue;
+            }
+            ".." => {
+                current.pop();
+                continue;
+            }
+            directive if directive.starts_with('/') => {
+                return Err(bad(format!("unknown directive {directive:?}")));
+            }
+            _ => {}
+        }
+
+        let name = unescape(first).map_err(&bad)?;
+        let mut keywords = defaults.clone();
+        for token in tokens {
+            // Bare flags such as `nochange` and `optional` carry no data.
+            if token.contains('=') {
+                let (key, value) = split_keyword(token).map_err(&bad)?;
+                keywords.insert(key.to_string(), value.to_string());
+            }
+        }
+
+        let full_path = name.contains('/');
+        let relative = if full_path {
+            PathBuf::from(&name)
+        } else {
+            current.join(&name)
+        };
+        let is_dir = keywords.get("type").is_some_and(|kind| kind == "dir");
+        if !full_path && is_dir && name != "." {
+            current.push(&name);
+        }
+
+        let path = lexical_join(&root, &relative);
+        if path == root {
+            continue;
+        }
+        if !path.starts_with(&root) {
+            return Err(bad(format!("entry {name:?} is outside the root")));
+        }
+        let record = build_record(&root, &root_value, &path, &keywords).map_err(&bad)?;
+        records.insert(record.relative_path.clone(), record);
+    }
+
+    Ok(records.into_values().collect())
+}
+
+/// Joins `\`-continued lines, yielding each logical line with the 1-based
+/// number of its first physical line. Blank and comment lines are dropped.
+fn logical_lines(text: &str) -> Vec<(usize, String)> {
+    let mut lines = Vec::new();
+    let mut pending: Option<(usize, String)> = None;
+    for (index, line) in text.lines().enumerate() {
+        let (start, mut joined) = pending.take().unwrap_or((index + 1, String::new()));
+        let trimmed = line.trim();
+        if joined.is_empty() && (trimmed.is_empty() || trimmed.starts_with('#')) {
+            continue;
+        }
+        match trimmed.strip_suffix('\\') {
+            Some(head) if !head.ends_with('\\') => {
+                joined.push_str(head);
+                joined.push(' ');
+                pending = Some((start, joined));
+            }
+            _ => {
+                joined.push_str(trimmed);
+                lines.push((start, joined));
+            }
+        }
+    }
+    lines.extend(pending);
+    lines
+}
+
+fn split_keyword(token: &str) -> Result<(&str, &str), String> {
+    token
+        .split_once('=')
+        .ok_or_else(|| format!("expected keyword=value, found {token:?}"))
+}
+
+fn build_record(
+    root: &Path,
+    root_value: &str,
+    path: &Path,
+    keywords: &BTreeMap<String, String>,
+) -> Result<VacuumRecord, String> {
+    let number = |key: &str| {
+        keywords
+            .get(key)
+            .map(|value| {
+                value
+                    .parse::<u64>()
+                    .map_err(|error| format!("{key}={value:?}: {error}"))
+            })
+            .transpose()
+    };
+    let text = |key: &str| keywords.get(key).map(|value| unescape(value)).transpose();
+
+    let mut record = VacuumRecord::empty();
+    record.path = native_string(path);
+    record.relative_path = normalize_relative(path.strip_prefix(root).unwrap_or(path));
+    record.root = root_value.to_string();
+    // mtree's default type is `file`.
+    let file_type = match keywords.get("type").map_or("file", String::as_str) {
+        "link" => "symlink",
+        kind @ ("file" | "dir" | "fifo" | "socket" | "block" | "char") => kind,
+        other => return Err(format!("unknown type {other:?}")),
+    };
+    record.file_type = Some(file_type.to_string());
+
+    if file_type != "dir" {
+        record.size = number("size")?;
+        record.extension = path
+            .extension()
+            .map(|value| format!(".{}", value.to_string_lossy()));
+        record.mime_guess = guess_from_extension(record.extension.as_deref()).map(str::to_string);
+    }
+    if let Some(time) = keywords.get("time") {
+        record.mtime = Some(
+            parse_time(time).ok_or_else(|| format!("time={time:?} is not seconds.nanoseconds"))?,
+        );
+    }
+    if let Some(mode) = keywords.get("mode") {
+        let bits =
+            u32::from_str_radix(mode, 8).map_err(|error| format!("mode={mode:?}: {error}"))?;
+        record.mode = Some(format_mode(bits));
+    }
+    record.uid = number("uid")?
+        .map(|uid| u32::try_from(uid).map_err(|error| format!("uid: {error}")))
+        .transpose()?;
+    record.gid = number("gid")?
+        .map(|gid| u32::try_from(gid).map_err(|error| format!("gid: {error}")))
+        .transpose()?;
+    record.user = text("uname")?;
+    record.group = text("gname")?;
+    if file_type == "symlink"
+        && let Some(target) = text("link")?
+    {
+        let parent = path.parent().unwrap_or(root);
+        record.symlink = Some(SymlinkInfo {
+            escapes_root: !lexical_join(parent, Path::new(&target)).starts_with(root),
+            target,
+            resolved: None,
+        });
+    }
+    Ok(record)
+}
+
+/// `seconds[.nanoseconds]` since the epoch, as an RFC 3339 millisecond string.
+fn parse_time(value: &str) -> Option<String> {
+    let (seconds, fraction) = value.split_once('.').unwrap_or((value, "0"));
+    if fraction.is_empty()
+        || fraction.len() > 9
+        || !fraction.bytes().all(|byte| byte.is_ascii_digit())
+    {
+        return None;
+    }
+    let nanos = format!("{fraction:0<9}").parse::<u32>().ok()?;
+    let time = DateTime::from_timestamp(seconds.parse().ok()?, nanos)?;
+    Some(time.to_rfc3339_opts(SecondsFormat::Millis, true))
+}
+
+/// Decodes `\ooo` octal escapes and the C-style escapes mtree writers use.
+fn unescape(value: &str) -> Result<String, String> {
+    let mut bytes = Vec::with_capacity(value.len());
+    let mut input = value.bytes();
+    while let Some(byte) = input.next() {
+        if byte != b'\\' {
+            bytes.push(byte);
+            continue;
+        }
+        let decoded = match input.next() {
+            Some(digit @ b'0'..=b'7') => {
+                let rest = [input.next(), input.next()];
+                let [Some(second @ b'0'..=b'7'), Some(third @ b'0'..=b'7')] = rest else {
+                    return Err(format!("bad octal escape in {value:?}"));
+                };
+                u8::try_from(
+                    u32::from(digit - b'0') * 64
+                        + u32::from(second - b'0') * 8
+                        + u32::from(third - b'0'),
+                )
+                .map_err(|_| format!("octal escape out of range in {value:?}"))?
+            }
+            Some(b's') => b' ',
+            Some(b't') => b'\t',
+            Some(b'n') => b'\n',
+            Some(b'r') => b'\r',
+            Some(b'\\') => b'\\',
+            _ => return Err(format!("bad escape in {value:?}")),
+        };
+        bytes.push(decoded);
+    }
+    String::from_utf8(bytes)
+        .map_err(|error| format!("{value:?} is not UTF-8 once unescaped: {error}"))
+}
+
+fn bad_mtree(spec: &Path, line: usize, error: &str) -> Refusal {
+    Refusal::new(
+        RefusalCode::BadMtree,
+        json!({
+            "mtree": spec.display().to_string(),
+            "line": line,
+            "error": error,
+        }),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::read_mtree;
+    use crate::refusal::codes::RefusalCode;
+
+    #[test]
+    fn relative_and_full_path_entries_become_records() {
+        let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+        let spec = temp_dir.path().join("tree.mtree");
+        fs::write(
+            &spec,
+            "#mtree\n\
+             /set type=file uid=0 gid=0 mode=0644\n\
+             . type=dir mode=0755\n\
+             q1 type=dir\n\
+             \x20   tape\\0401.csv size=42 time=1767225600.250000000 \\\n\
+             \x20       sha256digest=abc\n\
+             ..\n\
+             ./latest type=link link=q1/tape\\0401.csv\n\
+             ./bad-link type=link link=../../etc\n",
+        )
+        .expect("spec should be written");
+
+        let records = read_mtree(&spec, temp_dir.path()).expect("spec should parse");
+        let paths = records
+            .iter()
+            .map(|record| record.relative_path.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(paths, vec!["bad-link", "latest", "q1", "q1/tape 1.csv"]);
+
+        let tape = &records[3];
+        assert_eq!(tape.file_type.as_deref(), Some("file"));
+        assert_eq!(tape.size, Some(42));
+        assert_eq!(tape.mtime.as_deref(), Some("2026-01-01T00:00:00.250Z"));
+        assert_eq!(tape.mode.as_deref(), Some("0644"));
+        assert_eq!(tape.mime_guess.as_deref(), Some("text/csv"));
+
+        assert_eq!(records[2].file_type.as_deref(), Some("dir"));
+        assert_eq!(records[2].size, None);
+        let link = records[1].symlink.as_ref().expect("latest is a link");
+        assert_eq!(link.target, "q1/tape 1.csv");
+        assert!(!link.escapes_root);
+        assert!(
+            records[0]
+                .symlink
+                .as_ref()
+                .is_some_and(|link| link.escapes_root)
+        );
+    }
+
+    #[test]
+    fn malformed_entries_refuse_with_the_line_number() {
+        let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+        let spec = temp_dir.path().join("tree.mtree");
+        fs::write(&spec, "#mtree\n./a type=file\n./b type=door\n").expect("spec should be written");
+
+        let refusal = read_mtree(&spec, temp_dir.path()).expect_err("unknown type should refuse");
+        assert_eq!(refusal.code, RefusalCode::BadMtree);
+        assert_eq!(refusal.detail["line"], 3);
+    }
+}
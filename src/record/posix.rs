@@ -0,0 +1,130 @@
+use std::{fs::Metadata, time::SystemTime};
+
+#[cfg(unix)]
+use std::time::{Duration, UNIX_EPOCH};
+
+#[cfg(unix)]
+use std::collections::BTreeMap;
+
+use crate::record::builder::VacuumRecord;
+
+/// Fills the `--posix` fields from `metadata`. With `resolve_names`, `user` and
+/// `group` come from `/etc/passwd` and `/etc/group`; ids with no entry there
+/// (for example directory-service accounts) leave the name absent.
+#[cfg(unix)]
+pub fn apply_posix(record: &mut VacuumRecord, metadata: &Metadata, resolve_names: bool) {
+    use std::os::unix::fs::MetadataExt;
+
+    record.mode = Some(format_mode(metadata.mode()));
+    record.uid = Some(metadata.uid());
+    record.gid = Some(metadata.gid());
+    record.inode = Some(metadata.ino());
+    record.dev = Some(metadata.dev());
+    record.nlink = Some(metadata.nlink());
+
+    if resolve_names {
+        let names = owner_names();
+        record.user = names.users.get(&metadata.uid()).cloned();
+        record.group = names.groups.get(&metadata.gid()).cloned();
+    }
+}
+
+/// Non-Unix platforms have no POSIX ownership; the fields stay absent.
+#[cfg(not(unix))]
+pub fn apply_posix(_record: &mut VacuumRecord, _metadata: &Metadata, _resolve_names: bool) {}
+
+/// Inode change time (`st_ctime`), which std does not expose portably.
+#[cfg(unix)]
+pub fn change_time(metadata: &Metadata) -> Option<SystemTime> {
+    use std::os::unix::fs::MetadataExt;
+
+    let seconds = Duration::from_secs(metadata.ctime().unsigned_abs());
+    let nanos = Duration::from_nanos(u64::try_from(metadata.ctime_nsec()).ok()?);
+    if metadata.ctime() >= 0 {
+        UNIX_EPOCH.checked_add(seconds)?.checked_add(nanos)
+    } else {
+        UNIX_EPOCH.checked_sub(seconds)?.checked_add(nanos)
+    }
+}
+
+#[cfg(not(unix))]
+pub fn change_time(_metadata: &Metadata) -> Option<SystemTime> {
+    None
+}
+
+/// Permission, setuid/setgid and sticky bits as four octal digits, e.g. `0644`.
+pub fn format_mode(mode: u32) -> String {
+    format!("{:04o}", mode & 0o7777)
+}
+
+#[cfg(unix)]
+struct OwnerNames {
+    users: BTreeMap<u32, String>,
+    groups: BTreeMap<u32, String>,
+}
+
+/// Read once per process; every worker thread shares the table.
+#[cfg(unix)]
+fn owner_names() -> &'static OwnerNames {
+    use std::{fs, sync::OnceLock};
+
+    static NAMES: OnceLock<OwnerNames> = OnceLock::new();
+    NAMES.get_or_init(|| OwnerNames {
+        users: parse_id_file(&fs::read_to_string("/etc/passwd").unwrap_or_default()),
+        groups: parse_id_file(&fs::read_to_string("/etc/group").unwrap_or_default()),
+    })
+}
+
+/// Parses `name:password:id:...` lines, the shared prefix of `/etc/passwd` and
+/// `/etc/group`. The first entry for an id wins, matching `getpwuid`.
+#[cfg(unix)]
+fn parse_id_file(contents: &str) -> BTreeMap<u32, String> {
+    let mut names = BTreeMap::new();
+    for line in contents.lines() {
+        if line.starts_with('#') {
+            continue;
+        }
+        let mut fields = line.split(':');
+        let (Some(name), Some(_), Some(id)) = (fields.next(), fields.next(), fields.next()) else {
+            continue;
+        };
+        if let Ok(id) = id.parse::<u32>()
+            && !name.is_empty()
+        {
+            names.entry(id).or_insert_with(|| name.to_string());
+        }
+    }
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::format_mode;
+
+    #[test]
+    fn mode_keeps_permission_and_special_bits_only() {
+        assert_eq!(format_mode(0o100644), "0644");
+        assert_eq!(format_mode(0o040777), "0777");
+        assert_eq!(format_mode(0o104755), "4755");
+        assert_eq!(format_mode(0o7), "0007");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn id_files_map_ids_to_first_name() {
+        let passwd = "# local accounts\n\
+                      root:x:0:0:root:/root:/bin/bash\n\
+                      toor:x:0:0:alias:/root:/bin/sh\n\
+                      analyst:x:1001:1001::/home/analyst:/bin/bash\n\
+                      broken-line\n\
+                      nobody:x:notanumber:0::/:/bin/false\n";
+        let names = super::parse_id_file(passwd);
+
+        assert_eq!(names.len(), 2);
+        assert_eq!(names[&0], "root");
+        assert_eq!(names[&1001], "analyst");
+
+        let groups = super::parse_id_file("wheel:x:10:root,analyst\n");
+        assert_eq!(groups[&10], "wheel");
+    }
+}
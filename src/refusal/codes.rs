@@ -3,6 +3,12 @@ pub enum RefusalCode {
     RootNotFound,
     RootPermission,
     Io,
+    TooDeep,
+    TooManyFiles,
+    BadManifest,
+    SymlinkEscapesRoot,
+    BadPattern,
+    BadMtree,
 }
 
 impl RefusalCode {
@@ -11,6 +17,12 @@ impl RefusalCode {
             Self::RootNotFound => "E_ROOT_NOT_FOUND",
             Self::RootPermission => "E_ROOT_PERMISSION",
             Self::Io => "E_IO",
+            Self::TooDeep => "E_TOO_DEEP",
+            Self::TooManyFiles => "E_TOO_MANY_FILES",
+            Self::BadManifest => "E_BAD_MANIFEST",
+            Self::SymlinkEscapesRoot => "E_SYMLINK_ESCAPES_ROOT",
+            Self::BadPattern => "E_BAD_PATTERN",
+            Self::BadMtree => "E_BAD_MTREE",
         }
     }
 
@@ -19,6 +31,12 @@ impl RefusalCode {
             Self::RootNotFound => "Root path does not exist",
             Self::RootPermission => "Cannot read root directory",
             Self::Io => "Filesystem error during scan",
+            Self::TooDeep => "Scan exceeded --max-depth",
+            Self::TooManyFiles => "Scan exceeded --max-files",
+            Self::BadManifest => "Manifest is not valid vacuum.v0 JSONL",
+            Self::SymlinkEscapesRoot => "Symlink target is outside every scan root",
+            Self::BadPattern => "Include or exclude pattern does not compile",
+            Self::BadMtree => "mtree specification does not parse",
         }
     }
 }
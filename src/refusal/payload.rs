@@ -1,3 +1,5 @@
+use std::{io, path::Path};
+
 use serde::Serialize;
 use serde_json::{Value, json};
 
@@ -85,3 +87,35 @@ pub fn empty_roots_refusal() -> Refusal {
     )
     .with_next_command("vacuum .")
 }
+
+/// `field` is the flag the list came from, e.g. `roots_from`.
+pub fn list_refusal(field: &str, path: &Path, error: &io::Error) -> Refusal {
+    Refusal::new(
+        RefusalCode::Io,
+        json!({
+            field: path.display().to_string(),
+            "error": error.to_string(),
+        }),
+    )
+}
+
+pub fn bad_pattern_refusal(flag: &str, pattern: &str, error: impl std::fmt::Display) -> Refusal {
+    Refusal::new(
+        RefusalCode::BadPattern,
+        json!({
+            "flag": flag,
+            "pattern": pattern,
+            "error": error.to_string(),
+        }),
+    )
+}
+
+pub fn spill_refusal(spill_dir: &Path, error: &io::Error) -> Refusal {
+    Refusal::new(
+        RefusalCode::Io,
+        json!({
+            "spill_dir": spill_dir.display().to_string(),
+            "error": error.to_string(),
+        }),
+    )
+}
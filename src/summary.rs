@@ -0,0 +1,261 @@
+use std::{collections::BTreeMap, path::Path};
+
+use serde::Serialize;
+
+use crate::{
+    cli::exit,
+    record::{builder::VacuumRecord, manifest::read_manifest},
+    refusal::payload::render,
+};
+
+pub const SUMMARY_VERSION: &str = "vacuum.summary.v0";
+
+/// Key for records whose extension, MIME guess, or warning code is absent.
+const NONE_KEY: &str = "(none)";
+
+/// Top-level key for files that sit directly in their root.
+const ROOT_LEVEL_KEY: &str = ".";
+
+/// Reads a manifest and prints its rollup as one JSON line.
+pub fn dispatch(manifest: &Path) -> u8 {
+    let records = match read_manifest(manifest) {
+        Ok(records) => records,
+        Err(refusal) => {
+            println!("{}", render(&refusal));
+            return exit::REFUSAL;
+        }
+    };
+
+    let mut summary = Summary::default();
+    for record in &records {
+        summary.add(record);
+    }
+    match serde_json::to_string(&summary) {
+        Ok(rendered) => {
+            println!("{rendered}");
+            exit::SCAN_COMPLETE
+        }
+        Err(error) => {
+            eprintln!("vacuum: summary emission failed: {error}");
+            exit::REFUSAL
+        }
+    }
+}
+
+/// Counts for one group of records. `files` and `bytes` cover records that
+/// were stat'd; `skipped` counts `_skipped` records, which have no size.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct Tally {
+    pub files: u64,
+    pub bytes: u64,
+    pub skipped: u64,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct RootSummary {
+    #[serde(flatten)]
+    pub tally: Tally,
+    /// Keyed by the first component of `relative_path`.
+    pub top_level: BTreeMap<String, Tally>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct MtimeRange {
+    pub oldest: Option<String>,
+    pub newest: Option<String>,
+}
+
+/// Deterministic rollup of a record stream. Every map is a `BTreeMap`, so the
+/// rendered JSON depends only on the records, never on their order.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Summary {
+    pub version: &'static str,
+    pub records: u64,
+    #[serde(flatten)]
+    pub tally: Tally,
+    /// Directory records from `--emit-dirs`; never part of any tally.
+    pub dirs: u64,
+    pub mtime: MtimeRange,
+    pub roots: BTreeMap<String, RootSummary>,
+    pub extensions: BTreeMap<String, Tally>,
+    pub mime: BTreeMap<String, Tally>,
+    /// Skipped records per warning code; a record with several distinct codes
+    /// counts once under each.
+    pub skipped_by_code: BTreeMap<String, u64>,
+}
+
+impl Default for Summary {
+    fn default() -> Self {
+        Self {
+            version: SUMMARY_VERSION,
+            records: 0,
+            tally: Tally::default(),
+            dirs: 0,
+            mtime: MtimeRange::default(),
+            roots: BTreeMap::new(),
+            extensions: BTreeMap::new(),
+            mime: BTreeMap::new(),
+            skipped_by_code: BTreeMap::new(),
+        }
+    }
+}
+
+impl Summary {
+    pub fn add(&mut self, record: &VacuumRecord) {
+        self.records += 1;
+        let skipped = record._skipped == Some(true);
+        if skipped {
+            self.count_skipped_codes(record);
+        } else if record.file_type.as_deref() == Some("dir") {
+            self.dirs += 1;
+            return;
+        }
+
+        let size = record.size.unwrap_or_default();
+        let root = self.roots.entry(record.root.clone()).or_default();
+        let top_level = top_level_key(&record.relative_path);
+        for tally in [
+            &mut self.tally,
+            &mut root.tally,
+            root.top_level.entry(top_level).or_default(),
+            self.extensions
+                .entry(key_or_none(record.extension.as_deref()))
+                .or_default(),
+            self.mime
+                .entry(key_or_none(record.mime_guess.as_deref()))
+                .or_default(),
+        ] {
+            if skipped {
+                tally.skipped += 1;
+            } else {
+                tally.files += 1;
+                tally.bytes = tally.bytes.saturating_add(size);
+            }
+        }
+
+        if let Some(mtime) = record.mtime.as_deref().filter(|_| !skipped) {
+            // Record mtimes are fixed-width UTC RFC 3339, so string order is
+            // time order.
+            if self
+                .mtime
+                .oldest
+                .as_deref()
+                .is_none_or(|oldest| mtime < oldest)
+            {
+                self.mtime.oldest = Some(mtime.to_string());
+            }
+            if self
+                .mtime
+                .newest
+                .as_deref()
+                .is_none_or(|newest| mtime > newest)
+            {
+                self.mtime.newest = Some(mtime.to_string());
+            }
+        }
+    }
+
+    fn count_skipped_codes(&mut self, record: &VacuumRecord) {
+        let mut codes = record
+            ._warnings
+            .iter()
+            .flatten()
+            .map(|warning| warning.code.as_str())
+            .collect::<Vec<_>>();
+        codes.sort_unstable();
+        codes.dedup();
+        if codes.is_empty() {
+            codes.push(NONE_KEY);
+        }
+        for code in codes {
+            *self.skipped_by_code.entry(code.to_string()).or_default() += 1;
+        }
+    }
+}
+
+fn top_level_key(relative_path: &str) -> String {
+    match relative_path.split_once('/') {
+        Some((first, _)) => first.to_string(),
+        None => ROOT_LEVEL_KEY.to_string(),
+    }
+}
+
+fn key_or_none(value: Option<&str>) -> String {
+    value.unwrap_or(NONE_KEY).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::Summary;
+    use crate::record::builder::{VacuumRecord, Warning};
+
+    fn record(relative_path: &str, size: u64, mtime: &str) -> VacuumRecord {
+        let mut record = VacuumRecord::empty();
+        record.path = format!("/data/{relative_path}");
+        record.relative_path = relative_path.to_string();
+        record.root = "/data".to_string();
+        record.size = Some(size);
+        record.mtime = Some(mtime.to_string());
+        record.extension = Some(".csv".to_string());
+        record.mime_guess = Some("text/csv".to_string());
+        record.file_type = Some("file".to_string());
+        record
+    }
+
+    #[test]
+    fn rollup_groups_by_root_top_level_extension_and_code() {
+        let mut skipped = VacuumRecord::empty();
+        skipped.relative_path = "locked/secret.bin".to_string();
+        skipped.root = "/data".to_string();
+        skipped._skipped = Some(true);
+        skipped._warnings = Some(vec![Warning {
+            tool: "vacuum".to_string(),
+            code: "E_IO".to_string(),
+            message: "Permission denied".to_string(),
+            detail: json!({}),
+        }]);
+        let mut dir = VacuumRecord::empty();
+        dir.relative_path = "q1".to_string();
+        dir.root = "/data".to_string();
+        dir.file_type = Some("dir".to_string());
+
+        let records = [
+            record("q1/a.csv", 10, "2026-01-02T00:00:00.000Z"),
+            record("q1/b.csv", 5, "2026-01-01T00:00:00.000Z"),
+            record("top.csv", 1, "2026-03-01T00:00:00.000Z"),
+            skipped,
+            dir,
+        ];
+        let mut forward = Summary::default();
+        let mut reverse = Summary::default();
+        for record in &records {
+            forward.add(record);
+        }
+        for record in records.iter().rev() {
+            reverse.add(record);
+        }
+        assert_eq!(forward, reverse);
+
+        let rendered = serde_json::to_value(&forward).expect("summary serializes");
+        assert_eq!(rendered["records"], 5);
+        assert_eq!(rendered["files"], 3);
+        assert_eq!(rendered["bytes"], 16);
+        assert_eq!(rendered["skipped"], 1);
+        assert_eq!(rendered["dirs"], 1);
+        assert_eq!(rendered["mtime"]["oldest"], "2026-01-01T00:00:00.000Z");
+        assert_eq!(rendered["mtime"]["newest"], "2026-03-01T00:00:00.000Z");
+        assert_eq!(
+            rendered["roots"]["/data"]["top_level"],
+            json!({
+                ".": { "files": 1, "bytes": 1, "skipped": 0 },
+                "locked": { "files": 0, "bytes": 0, "skipped": 1 },
+                "q1": { "files": 2, "bytes": 15, "skipped": 0 },
+            })
+        );
+        assert_eq!(rendered["extensions"][".csv"]["files"], 3);
+        assert_eq!(rendered["extensions"]["(none)"]["skipped"], 1);
+        assert_eq!(rendered["skipped_by_code"], json!({ "E_IO": 1 }));
+    }
+}
@@ -0,0 +1,204 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    ops::ControlFlow,
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use serde_json::{Value, json};
+
+use crate::{
+    cli::exit,
+    diff::{diff_records, emit_events, key_records},
+    record::{builder::VacuumRecord, manifest::parse_manifest},
+    refusal::{
+        codes::RefusalCode,
+        payload::{Refusal, render},
+    },
+    walk::{
+        filter::{MetadataFilter, PathFilter, PathPatterns},
+        list::scan_file_list_into,
+        symlink::SymlinkPolicy,
+        walker::{ScanOptions, scan_roots_into},
+    },
+    witness::query::find_run_by_output_hash,
+};
+
+/// Re-scans the roots recorded in `manifest` and reports drift with the same
+/// events as `vacuum diff`. Exits 0 when the manifest is still faithful, 1
+/// when it drifted, 2 on refusal.
+pub fn dispatch(manifest: &Path) -> u8 {
+    match verify(manifest, find_run_by_output_hash) {
+        Ok(code) => code,
+        Err(refusal) => {
+            println!("{}", render(&refusal));
+            exit::REFUSAL
+        }
+    }
+}
+
+/// The roots to re-scan are the distinct `root` values in the manifest plus
+/// `params.scan_roots` of the witnessed run that wrote it, found through
+/// `find_run` by the manifest's BLAKE3. Only the ledger knows a root whose
+/// scan produced no records, and only its `params` know which files the run
+/// left out on purpose. Without a witness record the roots are re-scanned with
+/// the scan defaults.
+fn verify(manifest: &Path, find_run: impl Fn(&str) -> Option<Value>) -> Result<u8, Refusal> {
+    let bytes = fs::read(manifest).map_err(|error| {
+        Refusal::new(
+            RefusalCode::Io,
+            json!({
+                "manifest": manifest.display().to_string(),
+                "error": error.to_string(),
+            }),
+        )
+    })?;
+    let recorded = parse_manifest(manifest, &bytes)?;
+    let mut roots = recorded
+        .iter()
+        .map(|record| record.root.clone())
+        .collect::<BTreeSet<_>>();
+    let output_hash = format!("blake3:{}", blake3::hash(&bytes).to_hex());
+    let params = find_run(&output_hash).map_or(Value::Null, |run| run["params"].clone());
+    roots.extend(strings(&params, "scan_roots"));
+    let roots = roots.into_iter().map(PathBuf::from).collect::<Vec<_>>();
+    let settings = RunSettings::from_params(&params);
+    let recorded = key_records(manifest, recorded, false)?;
+
+    // A `--files-from` run only ever stat'ed the listed paths, all of which
+    // are in the manifest, missing ones as `_skipped` records.
+    let mut current = if settings.files_from {
+        key_records(manifest, restat(recorded.values(), &settings.scan)?, false)?
+    } else {
+        key_records(manifest, rescan(&roots, &settings)?, false)?
+    };
+    let missing = recorded.iter().filter(|(key, record)| {
+        !current.contains_key(*key) && fs::symlink_metadata(entry_path(record)).is_ok()
+    });
+    for record in restat(missing.map(|(_, record)| record), &settings.scan)? {
+        current.insert((record.relative_path.clone(), record.root.clone()), record);
+    }
+
+    Ok(emit_events(&diff_records(recorded, current)))
+}
+
+/// The witnessed run's filters and walk settings. Keys absent from `params`,
+/// as in records written before they existed, take the scan defaults.
+struct RunSettings {
+    include: Vec<String>,
+    exclude: Vec<String>,
+    include_regex: Vec<String>,
+    exclude_regex: Vec<String>,
+    predicates: MetadataFilter,
+    files_from: bool,
+    scan: ScanOptions,
+}
+
+impl RunSettings {
+    fn from_params(params: &Value) -> Self {
+        let flag = |key: &str| params[key].as_bool().unwrap_or(false);
+        let time = |key: &str| {
+            params[key]
+                .as_str()
+                .and_then(|value| DateTime::parse_from_rfc3339(value).ok())
+                .map(|time| time.with_timezone(&Utc))
+        };
+        let symlink_policy = params["symlink_policy"]
+            .as_str()
+            .and_then(|value| {
+                SymlinkPolicy::value_variants()
+                    .iter()
+                    .copied()
+                    .find(|policy| policy.as_str() == value)
+            })
+            .unwrap_or_default();
+        let exclude = strings(params, "exclude");
+
+        Self {
+            include: strings(params, "include"),
+            include_regex: strings(params, "include_regex"),
+            exclude_regex: strings(params, "exclude_regex"),
+            predicates: MetadataFilter {
+                min_size: params["min_size"].as_u64(),
+                max_size: params["max_size"].as_u64(),
+                newer_than: time("newer_than"),
+                older_than: time("older_than"),
+            },
+            files_from: params["files_from"].is_string(),
+            scan: ScanOptions {
+                no_ignore: flag("no_ignore"),
+                gitignore: flag("gitignore"),
+                exclude: exclude.clone(),
+                glob_case_insensitive: flag("glob_case_insensitive"),
+                emit_dirs: flag("emit_dirs"),
+                symlink_policy,
+                one_file_system: flag("one_file_system"),
+                ..ScanOptions::new(!flag("no_follow"))
+            },
+            exclude,
+        }
+    }
+}
+
+fn strings(params: &Value, key: &str) -> Vec<String> {
+    params[key]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .map(str::to_string)
+        .collect()
+}
+
+/// A root that vanished yields no records, so every file under it is
+/// reported as `removed`.
+fn rescan(roots: &[PathBuf], settings: &RunSettings) -> Result<Vec<VacuumRecord>, Refusal> {
+    let filter = PathFilter::from_patterns(&PathPatterns {
+        include: &settings.include,
+        exclude: &settings.exclude,
+        include_regex: &settings.include_regex,
+        exclude_regex: &settings.exclude_regex,
+        glob_case_insensitive: settings.scan.glob_case_insensitive,
+    })?;
+    let mut records = Vec::new();
+    scan_roots_into(roots, &settings.scan, |record| {
+        if filter.matches(&record.relative_path) && settings.predicates.matches(&record) {
+            records.push(record);
+        }
+        ControlFlow::Continue(())
+    })?;
+    Ok(records)
+}
+
+/// Builds fresh records for recorded entries by their path under the root. A
+/// path that still exists gets a record even if the filters or an ignore file
+/// now hide it, so it is compared rather than reported as `removed`.
+fn restat<'a>(
+    records: impl IntoIterator<Item = &'a VacuumRecord>,
+    options: &ScanOptions,
+) -> Result<Vec<VacuumRecord>, Refusal> {
+    let mut by_root = BTreeMap::<&str, Vec<PathBuf>>::new();
+    for record in records {
+        by_root
+            .entry(record.root.as_str())
+            .or_default()
+            .push(entry_path(record));
+    }
+
+    let mut records = Vec::new();
+    for (root, paths) in by_root {
+        scan_file_list_into(Path::new(root), &paths, options, |record| {
+            records.push(record);
+            ControlFlow::Continue(())
+        })?;
+    }
+    Ok(records)
+}
+
+/// `path` of a followed link is its resolved target, so the entry itself is
+/// found through `root` and `relative_path`.
+fn entry_path(record: &VacuumRecord) -> PathBuf {
+    Path::new(&record.root).join(&record.relative_path)
+}
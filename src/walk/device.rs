@@ -0,0 +1,148 @@
+use std::path::Path;
+
+use serde_json::json;
+use walkdir::DirEntry;
+
+use crate::{
+    record::builder::{VacuumRecord, Warning},
+    walk::walker::{ScanOptions, build_skipped_entry},
+};
+
+/// `--one-file-system`: the device one scan root lives on. Directories on any
+/// other device are recorded as mount points and never descended into.
+pub(crate) struct DeviceBoundary {
+    /// `None` without `--one-file-system`, or where devices are not exposed.
+    device: Option<u64>,
+}
+
+impl DeviceBoundary {
+    pub(crate) fn new(root: &Path, options: &ScanOptions) -> Self {
+        Self {
+            device: options.one_file_system.then(|| device_id(root)).flatten(),
+        }
+    }
+
+    /// Returns the device of a directory entry that lives on a different
+    /// device than the root. Under `--follow`, a linked directory is judged by
+    /// its target.
+    pub(crate) fn crossing(&self, entry: &DirEntry) -> Option<u64> {
+        let root_device = self.device?;
+        if !entry.file_type().is_dir() {
+            return None;
+        }
+        device_id(entry.path()).filter(|device| *device != root_device)
+    }
+
+    /// A `_skipped` directory record standing in for the untraversed mount
+    /// point, so the omitted subtree is explicit in the manifest. `W_OTHER_FILESYSTEM`
+    /// is appended to `warnings` already raised for the entry, such as
+    /// `W_SYMLINK_ESCAPES_ROOT` for a followed link.
+    pub(crate) fn marker(
+        &self,
+        root: &Path,
+        root_value: &str,
+        path: &Path,
+        device: u64,
+        mut warnings: Vec<Warning>,
+    ) -> VacuumRecord {
+        let mut record = build_skipped_entry(root, root_value, path, String::new());
+        record.file_type = Some("dir".to_string());
+        record.extension = None;
+        record.mime_guess = None;
+        warnings.push(Warning {
+            tool: "vacuum".to_string(),
+            code: "W_OTHER_FILESYSTEM".to_string(),
+            message: "Mount point on another filesystem not descended (--one-file-system)"
+                .to_string(),
+            detail: json!({ "device": device, "root_device": self.device }),
+        });
+        record._warnings = Some(warnings);
+        record
+    }
+}
+
+#[cfg(unix)]
+fn device_id(path: &Path) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+
+    std::fs::metadata(path).ok().map(|metadata| metadata.dev())
+}
+
+/// std exposes no volume identity off Unix; `--one-file-system` is inert there.
+#[cfg(not(unix))]
+fn device_id(_path: &Path) -> Option<u64> {
+    None
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use std::{os::unix::fs::symlink, path::Path};
+
+    use super::{DeviceBoundary, device_id};
+    use crate::walk::walker::{ScanOptions, scan_roots_with_options};
+
+    #[test]
+    fn markers_are_skipped_dir_records_naming_both_devices() {
+        let boundary = DeviceBoundary { device: Some(1) };
+        let marker = boundary.marker(
+            Path::new("/data"),
+            "/data",
+            Path::new("/data/mnt"),
+            2,
+            Vec::new(),
+        );
+
+        assert_eq!(marker.relative_path, "mnt");
+        assert_eq!(marker._skipped, Some(true));
+        assert_eq!(marker.file_type.as_deref(), Some("dir"));
+        let warnings = marker._warnings.as_ref().expect("marker has warnings");
+        assert_eq!(warnings[0].code, "W_OTHER_FILESYSTEM");
+        assert_eq!(warnings[0].detail["device"], 2);
+        assert_eq!(warnings[0].detail["root_device"], 1);
+    }
+
+    /// A followed link to `/proc` stands in for a mount point, since tests
+    /// cannot mount anything. procfs is its own device on every Linux host.
+    #[test]
+    fn mount_points_are_marked_and_not_descended() {
+        let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+        let root = temp_dir.path();
+        let proc_device = device_id(Path::new("/proc"));
+        assert!(proc_device.is_some(), "/proc should be mounted");
+        assert_ne!(
+            proc_device,
+            device_id(root),
+            "/proc should not share the temp dir's device"
+        );
+        std::fs::write(root.join("local.txt"), "x").expect("file should be written");
+        symlink("/proc", root.join("proc")).expect("link should be created");
+
+        for threads in [1, 3] {
+            let options = ScanOptions {
+                one_file_system: true,
+                threads,
+                ..ScanOptions::new(true)
+            };
+            let mut records = scan_roots_with_options(&[root.to_path_buf()], &options)
+                .expect("scan should not refuse");
+            records.sort_by(|left, right| left.relative_path.cmp(&right.relative_path));
+
+            let paths = records
+                .iter()
+                .map(|record| record.relative_path.as_str())
+                .collect::<Vec<_>>();
+            assert_eq!(paths, vec!["local.txt", "proc"]);
+
+            let marker = &records[1];
+            assert_eq!(marker._skipped, Some(true));
+            assert_eq!(marker.file_type.as_deref(), Some("dir"));
+            let warnings = marker._warnings.as_ref().expect("marker has warnings");
+            let codes = warnings
+                .iter()
+                .map(|warning| warning.code.as_str())
+                .collect::<Vec<_>>();
+            assert_eq!(codes, vec!["W_SYMLINK_ESCAPES_ROOT", "W_OTHER_FILESYSTEM"]);
+            assert_eq!(warnings[1].detail["device"], proc_device.unwrap());
+        }
+    }
+}
@@ -1,73 +1,323 @@
+use std::path::Path;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, SubsecRound, TimeDelta, Utc};
 use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use regex::{Regex, RegexSet};
+use walkdir::DirEntry;
 
-use crate::record::builder::VacuumRecord;
+use crate::{
+    record::{builder::VacuumRecord, path::normalize_relative},
+    refusal::payload::{Refusal, bad_pattern_refusal},
+};
 
 pub fn apply_filters(
     records: Vec<VacuumRecord>,
     include: &[String],
     exclude: &[String],
-) -> Vec<VacuumRecord> {
-    let include_set = compile_globset(include);
-    let exclude_set = compile_globset(exclude);
+) -> Result<Vec<VacuumRecord>, Refusal> {
+    let filter = PathFilter::new(include, exclude)?;
 
-    records
+    Ok(records
         .into_iter()
-        .filter(|record| {
-            let candidate = record.relative_path.as_str();
-
-            let include_match = if include.is_empty() {
-                true
-            } else {
-                include_set
-                    .as_ref()
-                    .map(|set| set.is_match(candidate))
-                    .unwrap_or(false)
-            };
-
-            if !include_match {
-                return false;
-            }
+        .filter(|record| filter.matches(&record.relative_path))
+        .collect())
+}
+
+/// Every pattern flag that selects records by `relative_path`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PathPatterns<'a> {
+    pub include: &'a [String],
+    pub exclude: &'a [String],
+    pub include_regex: &'a [String],
+    pub exclude_regex: &'a [String],
+    /// Applies to globs only; regexes opt in with `(?i)`.
+    pub glob_case_insensitive: bool,
+}
+
+/// Compiled `--include` / `--exclude` globs and `--include-regex` /
+/// `--exclude-regex` expressions, matched against `relative_path`. A record
+/// is kept when it matches any include of either kind (or none are given)
+/// and no exclude of either kind.
+pub struct PathFilter {
+    include: Option<GlobSet>,
+    exclude: Option<GlobSet>,
+    include_regex: Option<RegexSet>,
+    exclude_regex: Option<RegexSet>,
+}
 
-            let exclude_match = if exclude.is_empty() {
-                false
-            } else {
-                exclude_set
-                    .as_ref()
-                    .map(|set| set.is_match(candidate))
-                    .unwrap_or(false)
-            };
+impl PathFilter {
+    /// Case-sensitive globs only.
+    pub fn new(include: &[String], exclude: &[String]) -> Result<Self, Refusal> {
+        Self::from_patterns(&PathPatterns {
+            include,
+            exclude,
+            ..PathPatterns::default()
+        })
+    }
 
-            !exclude_match
+    /// Refuses with `E_BAD_PATTERN` naming the first pattern that does not compile.
+    pub fn from_patterns(patterns: &PathPatterns<'_>) -> Result<Self, Refusal> {
+        let case_insensitive = patterns.glob_case_insensitive;
+        Ok(Self {
+            include: compile_globset("--include", patterns.include, case_insensitive)?,
+            exclude: compile_globset("--exclude", patterns.exclude, case_insensitive)?,
+            include_regex: compile_regexset("--include-regex", patterns.include_regex)?,
+            exclude_regex: compile_regexset("--exclude-regex", patterns.exclude_regex)?,
         })
-        .collect()
+    }
+
+    pub fn matches(&self, candidate: &str) -> bool {
+        let has_include = self.include.is_some() || self.include_regex.is_some();
+        let include_match = !has_include
+            || self
+                .include
+                .as_ref()
+                .is_some_and(|set| set.is_match(candidate))
+            || self
+                .include_regex
+                .as_ref()
+                .is_some_and(|set| set.is_match(candidate));
+
+        if !include_match {
+            return false;
+        }
+
+        let exclude_match = self
+            .exclude
+            .as_ref()
+            .is_some_and(|set| set.is_match(candidate))
+            || self
+                .exclude_regex
+                .as_ref()
+                .is_some_and(|set| set.is_match(candidate));
+
+        !exclude_match
+    }
+}
+
+/// Directories whose whole subtree `--exclude` already drops. Only patterns of
+/// the form `P/**` qualify: when `P` matches a directory, every path below it
+/// matches the pattern, so skipping its children cannot change the manifest.
+/// The directory itself does not match `P/**`; under `--emit-dirs` the walkers
+/// still emit its record.
+pub struct DirPruner {
+    prefixes: Option<GlobSet>,
+}
+
+impl DirPruner {
+    /// `exclude` has already been validated by `PathFilter`; a prefix that
+    /// somehow fails to compile just disables pruning, which is always safe.
+    pub fn new(exclude: &[String], case_insensitive: bool) -> Self {
+        let prefixes = exclude
+            .iter()
+            .filter_map(|pattern| pattern.strip_suffix("/**"))
+            .filter(|prefix| !prefix.is_empty())
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+
+        Self {
+            prefixes: compile_globset("--exclude", &prefixes, case_insensitive)
+                .ok()
+                .flatten(),
+        }
+    }
+
+    pub fn prunes(&self, relative_dir: &str) -> bool {
+        self.prefixes
+            .as_ref()
+            .is_some_and(|set| set.is_match(relative_dir))
+    }
+
+    pub(crate) fn prunes_entry(&self, root: &Path, entry: &DirEntry) -> bool {
+        if self.prefixes.is_none() || !entry.file_type().is_dir() {
+            return false;
+        }
+        match entry.path().strip_prefix(root) {
+            Ok(relative) => self.prunes(&normalize_relative(relative)),
+            Err(_) => false,
+        }
+    }
+}
+
+/// `--min-size` / `--max-size` / `--newer-than` / `--older-than`, applied to
+/// each record alongside `PathFilter`. A record lacking the field a bound
+/// tests passes it: directories have no `size`, and `_skipped` records have
+/// neither field, so evidence of an unreadable entry is never filtered away.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MetadataFilter {
+    pub min_size: Option<u64>,
+    pub max_size: Option<u64>,
+    pub newer_than: Option<DateTime<Utc>>,
+    pub older_than: Option<DateTime<Utc>>,
+}
+
+impl MetadataFilter {
+    pub fn matches(&self, record: &VacuumRecord) -> bool {
+        if let Some(size) = record.size
+            && (self.min_size.is_some_and(|min| size < min)
+                || self.max_size.is_some_and(|max| size > max))
+        {
+            return false;
+        }
+        if self.newer_than.is_none() && self.older_than.is_none() {
+            return true;
+        }
+
+        match record
+            .mtime
+            .as_deref()
+            .and_then(|value| DateTime::parse_from_rfc3339(value).ok())
+        {
+            Some(mtime) => {
+                let mtime = mtime.with_timezone(&Utc);
+                !(self.newer_than.is_some_and(|cutoff| mtime < cutoff)
+                    || self.older_than.is_some_and(|cutoff| mtime >= cutoff))
+            }
+            None => true,
+        }
+    }
+}
+
+/// Parses a `--min-size` / `--max-size` value: bytes with an optional,
+/// case-insensitive unit. `K`/`KB` through `T`/`TB` are powers of 1000 and
+/// `KiB` through `TiB` powers of 1024; `1.5G` is allowed.
+pub fn parse_size(value: &str) -> Result<u64, String> {
+    let trimmed = value.trim();
+    let split = trimmed
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(trimmed.len());
+    let (number, unit) = trimmed.split_at(split);
+    let multiplier: u64 = match unit.trim().to_ascii_lowercase().as_str() {
+        "" | "b" => 1,
+        "k" | "kb" => 1_000,
+        "m" | "mb" => 1_000_000,
+        "g" | "gb" => 1_000_000_000,
+        "t" | "tb" => 1_000_000_000_000,
+        "kib" => 1 << 10,
+        "mib" => 1 << 20,
+        "gib" => 1 << 30,
+        "tib" => 1 << 40,
+        other => {
+            return Err(format!(
+                "unknown size unit `{other}` (expected B, KB, MB, GB, TB, KiB, MiB, GiB, or TiB)"
+            ));
+        }
+    };
+
+    if let Ok(whole) = number.parse::<u64>() {
+        return whole
+            .checked_mul(multiplier)
+            .ok_or_else(|| format!("size `{value}` is too large"));
+    }
+    let fraction = number
+        .parse::<f64>()
+        .ok()
+        .filter(|fraction| fraction.is_finite())
+        .ok_or_else(|| format!("invalid size `{value}`"))?;
+    let bytes = (fraction * multiplier as f64).round();
+    if bytes >= u64::MAX as f64 {
+        return Err(format!("size `{value}` is too large"));
+    }
+    Ok(bytes as u64)
 }
 
-fn compile_globset(patterns: &[String]) -> Option<GlobSet> {
+/// Parses a `--newer-than` / `--older-than` value into an absolute cutoff:
+/// an RFC 3339 timestamp, a UTC date or date-time (`2026-01-31`,
+/// `2026-01-31T08:00:00`), or a duration before now (`90s`, `30m`, `24h`,
+/// `7d`, `2w`). Resolving once at parse time keeps the cutoff fixed for the
+/// whole scan and lets the witness record it. The cutoff is truncated to
+/// milliseconds, the precision of record mtimes and of the witness, so
+/// replaying the recorded value selects exactly the same records.
+pub fn parse_time_bound(value: &str) -> Result<DateTime<Utc>, String> {
+    resolve_time_bound(value, Utc::now())
+}
+
+fn resolve_time_bound(value: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>, String> {
+    resolve_exact_time_bound(value, now).map(|time| time.trunc_subsecs(3))
+}
+
+fn resolve_exact_time_bound(value: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>, String> {
+    let trimmed = value.trim();
+    if let Ok(time) = DateTime::parse_from_rfc3339(trimmed) {
+        return Ok(time.with_timezone(&Utc));
+    }
+    if let Ok(time) = NaiveDateTime::parse_from_str(trimmed, "%Y-%m-%dT%H:%M:%S") {
+        return Ok(time.and_utc());
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(trimmed, "%Y-%m-%d") {
+        return Ok(date.and_time(chrono::NaiveTime::MIN).and_utc());
+    }
+
+    let invalid = || {
+        format!(
+            "invalid time `{value}` (expected an ISO-8601 timestamp, a date, or a duration like 24h or 7d)"
+        )
+    };
+    let split = trimmed
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(trimmed.len());
+    let (number, unit) = trimmed.split_at(split);
+    let amount = number.parse::<i64>().map_err(|_| invalid())?;
+    let duration = match unit {
+        "s" => TimeDelta::try_seconds(amount),
+        "m" => TimeDelta::try_minutes(amount),
+        "h" => TimeDelta::try_hours(amount),
+        "d" => TimeDelta::try_days(amount),
+        "w" => TimeDelta::try_weeks(amount),
+        _ => None,
+    }
+    .ok_or_else(invalid)?;
+    now.checked_sub_signed(duration).ok_or_else(invalid)
+}
+
+fn compile_globset(
+    flag: &str,
+    patterns: &[String],
+    case_insensitive: bool,
+) -> Result<Option<GlobSet>, Refusal> {
     if patterns.is_empty() {
-        return None;
+        return Ok(None);
     }
 
     let mut builder = GlobSetBuilder::new();
-    let mut has_pattern = false;
-
     for pattern in patterns {
-        if let Ok(glob) = GlobBuilder::new(pattern).literal_separator(true).build() {
-            builder.add(glob);
-            has_pattern = true;
-        }
+        let glob = GlobBuilder::new(pattern)
+            .literal_separator(true)
+            .case_insensitive(case_insensitive)
+            .build()
+            .map_err(|error| bad_pattern_refusal(flag, pattern, error.kind()))?;
+        builder.add(glob);
     }
 
-    if !has_pattern {
-        return None;
+    builder
+        .build()
+        .map(Some)
+        .map_err(|error| bad_pattern_refusal(flag, &patterns.join(" "), error))
+}
+
+fn compile_regexset(flag: &str, patterns: &[String]) -> Result<Option<RegexSet>, Refusal> {
+    if patterns.is_empty() {
+        return Ok(None);
     }
 
-    builder.build().ok()
+    // Compile one at a time first so the refusal names the offending pattern.
+    for pattern in patterns {
+        Regex::new(pattern).map_err(|error| bad_pattern_refusal(flag, pattern, error))?;
+    }
+    RegexSet::new(patterns)
+        .map(Some)
+        .map_err(|error| bad_pattern_refusal(flag, &patterns.join(" "), error))
 }
+
 #[cfg(test)]
 mod tests {
     use crate::record::builder::VacuumRecord;
 
-    use super::apply_filters;
+    use super::{
+        DirPruner, MetadataFilter, PathFilter, PathPatterns, apply_filters, parse_size,
+        resolve_time_bound,
+    };
+    use crate::refusal::codes::RefusalCode;
 
     fn record(relative_path: &str) -> VacuumRecord {
         let mut record = VacuumRecord::empty();
@@ -79,7 +329,7 @@ mod tests {
     fn include_patterns_are_or_matched() {
         let records = vec![record("alpha.csv"), record("beta.txt"), record("gamma.pdf")];
         let include = vec!["*.csv".to_string(), "*.txt".to_string()];
-        let filtered = apply_filters(records, &include, &[]);
+        let filtered = apply_filters(records, &include, &[]).expect("patterns should compile");
 
         let mut kept = filtered
             .into_iter()
@@ -95,7 +345,7 @@ mod tests {
         let records = vec![record("keep.csv"), record("drop.csv")];
         let include = vec!["*.csv".to_string()];
         let exclude = vec!["drop.*".to_string()];
-        let filtered = apply_filters(records, &include, &exclude);
+        let filtered = apply_filters(records, &include, &exclude).expect("patterns should compile");
 
         let kept = filtered
             .into_iter()
@@ -109,7 +359,7 @@ mod tests {
     fn matching_uses_record_relative_path_directly() {
         let records = vec![record("nested/inner/file.csv")];
         let include = vec!["nested/**/*.csv".to_string()];
-        let filtered = apply_filters(records, &include, &[]);
+        let filtered = apply_filters(records, &include, &[]).expect("patterns should compile");
 
         assert_eq!(filtered.len(), 1);
     }
@@ -119,7 +369,7 @@ mod tests {
     fn literal_backslashes_are_not_treated_as_separators() {
         let records = vec![record(r"nested\inner\file.csv")];
         let include = vec!["nested/**/*.csv".to_string()];
-        let filtered = apply_filters(records, &include, &[]);
+        let filtered = apply_filters(records, &include, &[]).expect("patterns should compile");
 
         assert!(filtered.is_empty());
     }
@@ -128,7 +378,7 @@ mod tests {
     fn star_pattern_matches_single_segment() {
         let records = vec![record("root.csv"), record("nested/root.csv")];
         let include = vec!["*.csv".to_string()];
-        let filtered = apply_filters(records, &include, &[]);
+        let filtered = apply_filters(records, &include, &[]).expect("patterns should compile");
 
         let kept = filtered
             .into_iter()
@@ -141,7 +391,7 @@ mod tests {
     fn double_star_pattern_matches_nested_segments() {
         let records = vec![record("root.csv"), record("nested/inner/root.csv")];
         let include = vec!["**/*.csv".to_string()];
-        let filtered = apply_filters(records, &include, &[]);
+        let filtered = apply_filters(records, &include, &[]).expect("patterns should compile");
         assert_eq!(filtered.len(), 2);
     }
 
@@ -149,7 +399,7 @@ mod tests {
     fn question_mark_pattern_matches_single_character() {
         let records = vec![record("a1/file.csv"), record("a12/file.csv")];
         let include = vec!["a?/file.csv".to_string()];
-        let filtered = apply_filters(records, &include, &[]);
+        let filtered = apply_filters(records, &include, &[]).expect("patterns should compile");
 
         let kept = filtered
             .into_iter()
@@ -166,7 +416,7 @@ mod tests {
             record("c1/file.csv"),
         ];
         let include = vec!["[ab]1/file.csv".to_string()];
-        let filtered = apply_filters(records, &include, &[]);
+        let filtered = apply_filters(records, &include, &[]).expect("patterns should compile");
 
         let mut kept = filtered
             .into_iter()
@@ -176,4 +426,142 @@ mod tests {
 
         assert_eq!(kept, vec!["a1/file.csv", "b1/file.csv"]);
     }
+
+    #[test]
+    fn only_trailing_double_star_excludes_prune_directories() {
+        let exclude = vec![
+            "archive/**".to_string(),
+            "**/node_modules/**".to_string(),
+            "*.tmp".to_string(),
+            "logs/*".to_string(),
+        ];
+        let pruner = DirPruner::new(&exclude, false);
+
+        assert!(pruner.prunes("archive"));
+        assert!(pruner.prunes("web/app/node_modules"));
+        assert!(!pruner.prunes("nested/archive"));
+        assert!(!pruner.prunes("scratch.tmp"));
+        assert!(!pruner.prunes("logs"));
+        assert!(!DirPruner::new(&[], false).prunes("archive"));
+    }
+
+    #[test]
+    fn case_insensitive_globs_and_regexes_compose() {
+        let include = vec!["*.csv".to_string()];
+        let include_regex = vec![r"^reports/\d{4}/".to_string()];
+        let exclude_regex = vec!["(?i)draft".to_string()];
+        let filter = PathFilter::from_patterns(&PathPatterns {
+            include: &include,
+            include_regex: &include_regex,
+            exclude_regex: &exclude_regex,
+            glob_case_insensitive: true,
+            ..PathPatterns::default()
+        })
+        .expect("patterns should compile");
+
+        assert!(filter.matches("VENDOR.CSV"));
+        assert!(filter.matches("reports/2026/summary.pdf"));
+        assert!(!filter.matches("reports/latest/summary.pdf"));
+        assert!(!filter.matches("DRAFT.csv"));
+
+        let sensitive = PathFilter::new(&include, &[]).expect("patterns should compile");
+        assert!(!sensitive.matches("VENDOR.CSV"));
+
+        let pruner = DirPruner::new(&["Archive/**".to_string()], true);
+        assert!(pruner.prunes("archive"));
+    }
+
+    #[test]
+    fn invalid_patterns_refuse_naming_the_pattern() {
+        let bad_glob = vec!["*.csv".to_string(), "data/[".to_string()];
+        let refusal = PathFilter::new(&[], &bad_glob)
+            .err()
+            .expect("unclosed class should refuse");
+        assert_eq!(refusal.code, RefusalCode::BadPattern);
+        assert_eq!(refusal.detail["flag"], "--exclude");
+        assert_eq!(refusal.detail["pattern"], "data/[");
+
+        let bad_regex = vec!["(unclosed".to_string()];
+        let refusal = PathFilter::from_patterns(&PathPatterns {
+            include_regex: &bad_regex,
+            ..PathPatterns::default()
+        })
+        .err()
+        .expect("unbalanced group should refuse");
+        assert_eq!(refusal.detail["flag"], "--include-regex");
+        assert_eq!(refusal.detail["pattern"], "(unclosed");
+    }
+
+    #[test]
+    fn sizes_accept_decimal_and_binary_units() {
+        assert_eq!(parse_size("0"), Ok(0));
+        assert_eq!(parse_size("512B"), Ok(512));
+        assert_eq!(parse_size("10GB"), Ok(10_000_000_000));
+        assert_eq!(parse_size("10g"), Ok(10_000_000_000));
+        assert_eq!(parse_size("4KiB"), Ok(4096));
+        assert_eq!(parse_size("1.5M"), Ok(1_500_000));
+        assert!(parse_size("10 parsecs").is_err());
+        assert!(parse_size("GB").is_err());
+        assert!(parse_size("99999999999TB").is_err());
+    }
+
+    #[test]
+    fn time_bounds_accept_timestamps_dates_and_durations() {
+        let now = chrono::DateTime::parse_from_rfc3339("2026-03-10T12:00:00Z")
+            .expect("timestamp should parse")
+            .to_utc();
+        let at = |value: &str| {
+            resolve_time_bound(value, now)
+                .expect("bound should parse")
+                .to_rfc3339()
+        };
+
+        assert_eq!(at("2026-01-31T08:00:00+02:00"), "2026-01-31T06:00:00+00:00");
+        assert_eq!(at("2026-01-31T08:00:00"), "2026-01-31T08:00:00+00:00");
+        assert_eq!(at("2026-01-31"), "2026-01-31T00:00:00+00:00");
+        assert_eq!(at("24h"), "2026-03-09T12:00:00+00:00");
+        assert_eq!(at("2w"), "2026-02-24T12:00:00+00:00");
+        assert_eq!(
+            at("2026-01-31T08:00:00.123456789Z"),
+            "2026-01-31T08:00:00.123+00:00"
+        );
+        let sub_millisecond_now = now + chrono::TimeDelta::nanoseconds(987_654_321);
+        assert_eq!(
+            resolve_time_bound("1h", sub_millisecond_now)
+                .expect("bound should parse")
+                .to_rfc3339(),
+            "2026-03-10T11:00:00.987+00:00"
+        );
+        assert!(resolve_time_bound("yesterday", now).is_err());
+        assert!(resolve_time_bound("5y", now).is_err());
+    }
+
+    #[test]
+    fn metadata_bounds_keep_records_missing_the_tested_field() {
+        let cutoff = chrono::DateTime::parse_from_rfc3339("2026-01-01T00:00:00Z")
+            .expect("timestamp should parse")
+            .to_utc();
+        let filter = MetadataFilter {
+            min_size: Some(1),
+            max_size: Some(100),
+            newer_than: Some(cutoff),
+            older_than: None,
+        };
+        let file = |size: u64, mtime: &str| {
+            let mut record = record("file.csv");
+            record.size = Some(size);
+            record.mtime = Some(mtime.to_string());
+            record
+        };
+
+        assert!(filter.matches(&file(10, "2026-02-01T00:00:00.000Z")));
+        assert!(filter.matches(&file(10, "2026-01-01T00:00:00.000Z")));
+        assert!(!filter.matches(&file(0, "2026-02-01T00:00:00.000Z")));
+        assert!(!filter.matches(&file(101, "2026-02-01T00:00:00.000Z")));
+        assert!(!filter.matches(&file(10, "2025-12-31T23:59:59.999Z")));
+
+        let mut skipped = record("unreadable.csv");
+        skipped._skipped = Some(true);
+        assert!(filter.matches(&skipped));
+    }
 }
@@ -0,0 +1,355 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::Path,
+    sync::{Arc, Mutex, PoisonError},
+};
+
+use globset::{GlobBuilder, GlobMatcher};
+use serde_json::{Value, json};
+use walkdir::DirEntry;
+
+use crate::{
+    record::path::{native_string, normalize_relative},
+    walk::walker::ScanOptions,
+};
+
+/// Per-directory ignore file, honored unless `--no-ignore` is given.
+pub const VACUUMIGNORE: &str = ".vacuumignore";
+/// Honored only with `--gitignore`; rules in `.vacuumignore` win on conflict.
+pub const GITIGNORE: &str = ".gitignore";
+
+/// Ignore rules in effect for one directory: its own file(s) plus every
+/// ancestor's, up to the scan root. Ignore files above the root are not read.
+pub(crate) struct IgnoreChain {
+    /// Directory holding the rules, relative to the scan root ("" for the root).
+    base: String,
+    rules: Vec<IgnoreRule>,
+    parent: Option<Arc<IgnoreChain>>,
+}
+
+struct IgnoreRule {
+    matcher: GlobMatcher,
+    negated: bool,
+    dir_only: bool,
+}
+
+/// Ignore files read during a scan, keyed by path, with the BLAKE3 of the
+/// bytes read. Witness `params.ignore_files` lists them, so a manifest shows
+/// which rules shaped it.
+#[derive(Debug, Default)]
+pub struct IgnoreLog {
+    files: Mutex<BTreeMap<String, String>>,
+}
+
+impl IgnoreLog {
+    fn record(&self, path: &Path, bytes: &[u8]) {
+        self.files
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(
+                native_string(path),
+                format!("blake3:{}", blake3::hash(bytes).to_hex()),
+            );
+    }
+
+    /// `[{ "path", "hash" }]` in path order.
+    pub fn to_json(&self) -> Value {
+        let files = self.files.lock().unwrap_or_else(PoisonError::into_inner);
+        files
+            .iter()
+            .map(|(path, hash)| json!({ "path": path, "hash": hash }))
+            .collect()
+    }
+}
+
+/// Reads the ignore files in `dir` and stacks them on `parent`. Returns
+/// `parent` unchanged when `dir` contributes no rules. Unreadable ignore files
+/// are treated as absent, which can only widen the manifest.
+pub(crate) fn load_ignores(
+    parent: Option<&Arc<IgnoreChain>>,
+    root: &Path,
+    dir: &Path,
+    options: &ScanOptions,
+) -> Option<Arc<IgnoreChain>> {
+    if options.no_ignore {
+        return None;
+    }
+
+    let mut rules = Vec::new();
+    let names = [(GITIGNORE, options.gitignore), (VACUUMIGNORE, true)];
+    for (name, enabled) in names {
+        if !enabled {
+            continue;
+        }
+        let path = dir.join(name);
+        if let Ok(bytes) = fs::read(&path)
+            && let Ok(contents) = std::str::from_utf8(&bytes)
+        {
+            rules.extend(contents.lines().filter_map(parse_rule));
+            if let Some(log) = &options.ignore_log {
+                log.record(&path, &bytes);
+            }
+        }
+    }
+
+    if rules.is_empty() {
+        return parent.cloned();
+    }
+
+    let base = match dir.strip_prefix(root) {
+        Ok(relative) => normalize_relative(relative),
+        Err(_) => String::new(),
+    };
+    Some(Arc::new(IgnoreChain {
+        base,
+        rules,
+        parent: parent.cloned(),
+    }))
+}
+
+/// Tracks the chain for each open directory of a depth-first walkdir walk,
+/// for use as its `filter_entry` predicate.
+pub(crate) struct IgnoreStack<'a> {
+    root: &'a Path,
+    options: &'a ScanOptions,
+    /// `(depth, chain for that directory's children)`, innermost last.
+    levels: Vec<(usize, Option<Arc<IgnoreChain>>)>,
+}
+
+impl<'a> IgnoreStack<'a> {
+    pub(crate) fn new(root: &'a Path, options: &'a ScanOptions) -> Self {
+        Self {
+            root,
+            options,
+            levels: vec![(0, load_ignores(None, root, root, options))],
+        }
+    }
+
+    /// Returns `false` for ignored entries so walkdir never descends into them.
+    pub(crate) fn admit(&mut self, entry: &DirEntry) -> bool {
+        let depth = entry.depth();
+        if depth == 0 {
+            return true;
+        }
+        while self.levels.len() > 1 && self.levels.last().is_some_and(|(open, _)| *open >= depth) {
+            self.levels.pop();
+        }
+        let parent = self.levels.last().and_then(|(_, chain)| chain.clone());
+
+        if ignores_entry(parent.as_ref(), self.root, entry) {
+            return false;
+        }
+        if entry.file_type().is_dir() {
+            let chain = load_ignores(parent.as_ref(), self.root, entry.path(), self.options);
+            self.levels.push((depth, chain));
+        }
+        true
+    }
+}
+
+pub(crate) fn ignores_entry(
+    chain: Option<&Arc<IgnoreChain>>,
+    root: &Path,
+    entry: &DirEntry,
+) -> bool {
+    if chain.is_none() {
+        return false;
+    }
+    match entry.path().strip_prefix(root) {
+        Ok(relative) => is_ignored(
+            chain,
+            &normalize_relative(relative),
+            entry.file_type().is_dir(),
+        ),
+        Err(_) => false,
+    }
+}
+
+/// Applies gitignore precedence: the deepest ignore file with a matching
+/// pattern decides, and within a file the last matching pattern wins.
+pub(crate) fn is_ignored(
+    chain: Option<&Arc<IgnoreChain>>,
+    relative_path: &str,
+    is_dir: bool,
+) -> bool {
+    let mut current = chain;
+    while let Some(level) = current {
+        let candidate = if level.base.is_empty() {
+            Some(relative_path)
+        } else {
+            relative_path
+                .strip_prefix(level.base.as_str())
+                .and_then(|rest| rest.strip_prefix('/'))
+        };
+
+        if let Some(candidate) = candidate
+            && let Some(rule) = level
+                .rules
+                .iter()
+                .rev()
+                .find(|rule| (is_dir || !rule.dir_only) && rule.matcher.is_match(candidate))
+        {
+            return !rule.negated;
+        }
+        current = level.parent.as_ref();
+    }
+
+    false
+}
+
+/// Parses one gitignore line. A pattern with a `/` before its last character
+/// is anchored to the ignore file's directory; otherwise it matches at any
+/// depth below it.
+fn parse_rule(line: &str) -> Option<IgnoreRule> {
+    let line = trim_unescaped_trailing_spaces(line);
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+
+    let (negated, pattern) = match line.strip_prefix('!') {
+        Some(rest) => (true, rest),
+        None => (false, line),
+    };
+    let (dir_only, pattern) = match pattern.strip_suffix('/') {
+        Some(rest) => (true, rest),
+        None => (false, pattern),
+    };
+    if pattern.is_empty() {
+        return None;
+    }
+
+    let glob = if let Some(anchored) = pattern.strip_prefix('/') {
+        anchored.to_string()
+    } else if pattern.contains('/') {
+        pattern.to_string()
+    } else {
+        format!("**/{pattern}")
+    };
+    let matcher = GlobBuilder::new(&glob)
+        .literal_separator(true)
+        .build()
+        .ok()?
+        .compile_matcher();
+
+    Some(IgnoreRule {
+        matcher,
+        negated,
+        dir_only,
+    })
+}
+
+fn trim_unescaped_trailing_spaces(line: &str) -> &str {
+    let line = line.strip_suffix('\r').unwrap_or(line);
+    let trimmed = line.trim_end_matches(' ');
+    if trimmed.len() < line.len() && trimmed.ends_with('\\') {
+        &line[..trimmed.len() + 1]
+    } else {
+        trimmed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::Path, sync::Arc};
+
+    use super::{IgnoreChain, is_ignored, load_ignores};
+    use crate::walk::walker::ScanOptions;
+
+    fn chain(root: &Path, dirs: &[&str]) -> Option<Arc<IgnoreChain>> {
+        let options = ScanOptions::new(true);
+        let mut chain = load_ignores(None, root, root, &options);
+        for dir in dirs {
+            chain = load_ignores(chain.as_ref(), root, &root.join(dir), &options);
+        }
+        chain
+    }
+
+    #[test]
+    fn unanchored_patterns_match_at_any_depth() {
+        let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+        fs::write(
+            temp_dir.path().join(".vacuumignore"),
+            "*.log\n# comment\n\n",
+        )
+        .expect("ignore file should be written");
+        let chain = chain(temp_dir.path(), &[]);
+
+        assert!(is_ignored(chain.as_ref(), "run.log", false));
+        assert!(is_ignored(chain.as_ref(), "deep/inner/run.log", false));
+        assert!(!is_ignored(chain.as_ref(), "run.csv", false));
+    }
+
+    #[test]
+    fn slash_anchors_patterns_to_the_ignore_file_directory() {
+        let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+        fs::write(
+            temp_dir.path().join(".vacuumignore"),
+            "/build\ndocs/*.tmp\n",
+        )
+        .expect("ignore file should be written");
+        let chain = chain(temp_dir.path(), &[]);
+
+        assert!(is_ignored(chain.as_ref(), "build", true));
+        assert!(!is_ignored(chain.as_ref(), "src/build", true));
+        assert!(is_ignored(chain.as_ref(), "docs/a.tmp", false));
+        assert!(!is_ignored(chain.as_ref(), "docs/sub/a.tmp", false));
+    }
+
+    #[test]
+    fn trailing_slash_only_matches_directories() {
+        let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+        fs::write(temp_dir.path().join(".vacuumignore"), "cache/\n")
+            .expect("ignore file should be written");
+        let chain = chain(temp_dir.path(), &[]);
+
+        assert!(is_ignored(chain.as_ref(), "nested/cache", true));
+        assert!(!is_ignored(chain.as_ref(), "nested/cache", false));
+    }
+
+    #[test]
+    fn negation_and_deeper_files_override_earlier_rules() {
+        let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("keep")).expect("subdirectory should be created");
+        fs::write(root.join(".vacuumignore"), "*.csv\n!important.csv\n")
+            .expect("ignore file should be written");
+        fs::write(root.join("keep/.vacuumignore"), "!*.csv\n")
+            .expect("ignore file should be written");
+
+        let top = chain(root, &[]);
+        assert!(is_ignored(top.as_ref(), "data.csv", false));
+        assert!(!is_ignored(top.as_ref(), "important.csv", false));
+
+        let nested = chain(root, &["keep"]);
+        assert!(!is_ignored(nested.as_ref(), "keep/data.csv", false));
+    }
+
+    #[test]
+    fn gitignore_is_opt_in_and_vacuumignore_wins() {
+        let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+        let root = temp_dir.path();
+        fs::write(root.join(".gitignore"), "*.csv\n*.log\n").expect("gitignore should be written");
+        fs::write(root.join(".vacuumignore"), "!keep.csv\n")
+            .expect("ignore file should be written");
+
+        let default = chain(root, &[]);
+        assert!(!is_ignored(default.as_ref(), "run.log", false));
+
+        let options = ScanOptions {
+            gitignore: true,
+            ..ScanOptions::new(true)
+        };
+        let with_git = load_ignores(None, root, root, &options);
+        assert!(is_ignored(with_git.as_ref(), "run.log", false));
+        assert!(is_ignored(with_git.as_ref(), "other.csv", false));
+        assert!(!is_ignored(with_git.as_ref(), "keep.csv", false));
+
+        let options = ScanOptions {
+            gitignore: true,
+            no_ignore: true,
+            ..ScanOptions::new(true)
+        };
+        assert!(load_ignores(None, root, root, &options).is_none());
+    }
+}
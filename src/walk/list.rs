@@ -0,0 +1,205 @@
+use std::{
+    collections::BTreeSet,
+    fs,
+    ops::ControlFlow,
+    path::{Path, PathBuf},
+};
+
+use serde_json::json;
+use walkdir::WalkDir;
+
+use crate::{
+    record::{
+        builder::{VacuumRecord, Warning},
+        path::{native_string, normalize_relative},
+    },
+    refusal::payload::Refusal,
+    walk::{
+        symlink::{Containment, lexical_join},
+        walker::{
+            ProgressReporter, ScanLimits, ScanOptions, absolute_root, build_record,
+            build_skipped_entry, emit_warning_for_skipped, io_warning,
+        },
+    },
+};
+
+/// `--files-from`: builds one record per listed path instead of walking
+/// `root`. Paths resolve against `root` and duplicates collapse to one record.
+/// A listed directory yields a directory record and is not descended into.
+/// Missing or unreadable paths become `_skipped` records, and paths that
+/// resolve outside `root` become `_skipped` `W_OUTSIDE_ROOT` records.
+pub fn scan_file_list_into<F>(
+    root: &Path,
+    files: &[PathBuf],
+    options: &ScanOptions,
+    mut sink: F,
+) -> Result<(), Refusal>
+where
+    F: FnMut(VacuumRecord) -> ControlFlow<()>,
+{
+    let absolute_root = absolute_root(root);
+    let root_value = native_string(&absolute_root);
+    let limits = ScanLimits::new(options);
+    let containment = Containment::new(std::slice::from_ref(&absolute_root), options);
+    let mut progress = ProgressReporter::new(options.progress_enabled);
+    let mut seen = BTreeSet::new();
+    let mut outcome = Ok(());
+
+    for listed in files {
+        let path = lexical_join(&absolute_root, listed);
+        if !seen.insert(path.clone()) {
+            continue;
+        }
+
+        let record = if path == absolute_root || !path.starts_with(&absolute_root) {
+            build_outside_root_entry(&root_value, &path, listed)
+        } else {
+            match build_listed_record(&absolute_root, &root_value, &path, &containment, options) {
+                Ok(record) => record,
+                Err(refusal) => {
+                    outcome = Err(refusal);
+                    break;
+                }
+            }
+        };
+
+        if let Err(refusal) = limits.count_record(&absolute_root, &record) {
+            outcome = Err(refusal);
+            break;
+        }
+        progress.record_processed();
+        progress.emit_if_due();
+        emit_warning_for_skipped(&record, options.progress_enabled);
+
+        if sink(record).is_break() {
+            break;
+        }
+    }
+
+    progress.emit_final();
+    outcome
+}
+
+/// Goes through walkdir so a listed path gets exactly the record the walker
+/// would have built for it.
+fn build_listed_record(
+    root: &Path,
+    root_value: &str,
+    path: &Path,
+    containment: &Containment,
+    options: &ScanOptions,
+) -> Result<VacuumRecord, Refusal> {
+    if let Err(error) = fs::symlink_metadata(path) {
+        let mut record = build_skipped_entry(root, root_value, path, error.to_string());
+        record._warnings = Some(vec![io_warning(
+            format!("Cannot read listed path: {error}"),
+            error.to_string(),
+        )]);
+        return Ok(record);
+    }
+
+    let entry = WalkDir::new(path)
+        .follow_links(options.follow_symlinks)
+        .into_iter()
+        .next();
+    match entry {
+        Some(Ok(entry)) => {
+            let escaped = containment.check(root, &entry)?;
+            Ok(build_record(
+                root,
+                root_value,
+                &entry,
+                escaped.as_deref(),
+                options,
+            ))
+        }
+        Some(Err(error)) => Ok(build_skipped_entry(
+            root,
+            root_value,
+            path,
+            error.to_string(),
+        )),
+        None => Ok(build_skipped_entry(
+            root,
+            root_value,
+            path,
+            "no directory entry".to_string(),
+        )),
+    }
+}
+
+fn build_outside_root_entry(root_value: &str, path: &Path, listed: &Path) -> VacuumRecord {
+    let mut record = VacuumRecord::empty();
+    record.path = native_string(path);
+    record.relative_path = normalize_relative(listed);
+    record.root = root_value.to_string();
+    record._skipped = Some(true);
+    record._warnings = Some(vec![Warning {
+        tool: "vacuum".to_string(),
+        code: "W_OUTSIDE_ROOT".to_string(),
+        message: "Listed path is not below the root".to_string(),
+        detail: json!({ "listed": native_string(listed) }),
+    }]);
+    record
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        ops::ControlFlow,
+        path::{Path, PathBuf},
+    };
+
+    use super::scan_file_list_into;
+    use crate::{record::builder::VacuumRecord, walk::walker::ScanOptions};
+
+    fn scan(root: &Path, files: &[&str]) -> Vec<VacuumRecord> {
+        let files = files.iter().map(PathBuf::from).collect::<Vec<_>>();
+        let mut records = Vec::new();
+        scan_file_list_into(root, &files, &ScanOptions::new(true), |record| {
+            records.push(record);
+            ControlFlow::Continue(())
+        })
+        .expect("list scan should not refuse");
+        records
+    }
+
+    #[test]
+    fn listed_files_are_recorded_without_walking_siblings() {
+        let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("data")).expect("data dir should be created");
+        fs::write(root.join("data/a.csv"), "a").expect("file should be written");
+        fs::write(root.join("data/b.csv"), "bb").expect("file should be written");
+
+        let records = scan(root, &["data/b.csv", "./data/b.csv", "data/../data/b.csv"]);
+
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].relative_path, "data/b.csv");
+        assert_eq!(records[0].size, Some(2));
+        assert_eq!(records[0]._skipped, None);
+    }
+
+    #[test]
+    fn missing_and_outside_paths_become_skipped_records() {
+        let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+        let root = temp_dir.path().join("root");
+        fs::create_dir_all(root.join("dir")).expect("root should be created");
+
+        let records = scan(&root, &["gone.txt", "../elsewhere.txt", "dir"]);
+
+        assert_eq!(records[0].relative_path, "gone.txt");
+        assert_eq!(records[0]._skipped, Some(true));
+        let warnings = records[0]._warnings.as_ref().expect("missing has warnings");
+        assert_eq!(warnings[0].code, "E_IO");
+        assert!(warnings[0].message.starts_with("Cannot read listed path"));
+
+        assert_eq!(records[1]._skipped, Some(true));
+        let warnings = records[1]._warnings.as_ref().expect("outside has warnings");
+        assert_eq!(warnings[0].code, "W_OUTSIDE_ROOT");
+
+        assert_eq!(records[2].file_type.as_deref(), Some("dir"));
+        assert_eq!(records[2].size, None);
+    }
+}
@@ -1,2 +1,7 @@
+pub mod device;
 pub mod filter;
+pub mod ignore;
+pub mod list;
+pub mod parallel;
+pub mod symlink;
 pub mod walker;
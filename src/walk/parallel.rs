@@ -0,0 +1,426 @@
+use std::{
+    fs,
+    ops::ControlFlow,
+    path::{Path, PathBuf},
+    sync::{
+        Arc, Condvar, Mutex, MutexGuard, PoisonError,
+        mpsc::{self, SyncSender},
+    },
+    thread,
+};
+
+use walkdir::WalkDir;
+
+use crate::{
+    record::{builder::VacuumRecord, path::native_string},
+    refusal::payload::Refusal,
+    walk::{
+        device::DeviceBoundary,
+        filter::DirPruner,
+        ignore::{IgnoreChain, ignores_entry, load_ignores},
+        symlink::{Containment, SymlinkPolicy},
+        walker::{
+            ProgressReporter, ScanLimits, ScanOptions, absolute_root, build_record,
+            build_skipped_entry, build_skipped_from_walk_error, emit_warning_for_skipped,
+            escape_warning,
+        },
+    },
+};
+
+/// Records buffered between the workers and the sink before workers block.
+const RECORD_CHANNEL_CAPACITY: usize = 4096;
+
+/// Walks every root with `options.threads` workers that each read one directory
+/// at a time. Records reach `sink` unordered; callers rely on the
+/// `(relative_path, root)` sort in `output` for determinism.
+pub(crate) fn scan_roots_parallel<F>(
+    roots: &[PathBuf],
+    options: &ScanOptions,
+    mut sink: F,
+) -> Result<(), Refusal>
+where
+    F: FnMut(VacuumRecord) -> ControlFlow<()>,
+{
+    let queue = WorkQueue::default();
+    for root in roots {
+        let absolute_root = absolute_root(root);
+        let scan_root = Arc::new(ScanRoot {
+            value: native_string(&absolute_root),
+            boundary: DeviceBoundary::new(&absolute_root, options),
+            path: absolute_root.clone(),
+        });
+        queue.push(DirTask {
+            root: scan_root,
+            dir: absolute_root,
+            depth: 0,
+            ancestors: None,
+            ignores: None,
+        });
+    }
+
+    let shared = SharedState {
+        options,
+        limits: ScanLimits::new(options),
+        pruner: DirPruner::new(&options.exclude, options.glob_case_insensitive),
+        containment: Containment::new(roots, options),
+        progress: Mutex::new(ProgressReporter::new(options.progress_enabled)),
+        refusal: Mutex::new(None),
+    };
+    let (sender, receiver) = mpsc::sync_channel(RECORD_CHANNEL_CAPACITY);
+    thread::scope(|scope| {
+        for _ in 0..options.threads {
+            let sender = sender.clone();
+            let queue = &queue;
+            let shared = &shared;
+            scope.spawn(move || {
+                while let Some(task) = queue.next() {
+                    match visit_directory(&task, shared, &sender) {
+                        Some(children) => queue.complete(children),
+                        None => {
+                            queue.cancel();
+                            queue.complete(Vec::new());
+                        }
+                    }
+                }
+            });
+        }
+        drop(sender);
+
+        for record in receiver {
+            if sink(record).is_break() {
+                queue.cancel();
+                break;
+            }
+        }
+    });
+
+    lock(&shared.progress).emit_final();
+    match shared
+        .refusal
+        .into_inner()
+        .unwrap_or_else(PoisonError::into_inner)
+    {
+        Some(refusal) => Err(refusal),
+        None => Ok(()),
+    }
+}
+
+struct SharedState<'a> {
+    options: &'a ScanOptions,
+    limits: ScanLimits,
+    pruner: DirPruner,
+    containment: Containment,
+    progress: Mutex<ProgressReporter>,
+    /// First guardrail refusal raised by any worker.
+    refusal: Mutex<Option<Refusal>>,
+}
+
+impl SharedState<'_> {
+    fn refuse(&self, refusal: Refusal) {
+        lock(&self.refusal).get_or_insert(refusal);
+    }
+}
+
+struct ScanRoot {
+    path: PathBuf,
+    value: String,
+    boundary: DeviceBoundary,
+}
+
+struct DirTask {
+    root: Arc<ScanRoot>,
+    dir: PathBuf,
+    depth: usize,
+    ancestors: Option<Arc<Ancestor>>,
+    /// Ignore rules inherited from the parent directory; `dir`'s own ignore
+    /// files are read when the task is visited.
+    ignores: Option<Arc<IgnoreChain>>,
+}
+
+/// One directory on the path from the scan root to a queued directory. Only
+/// tracked when following symlinks, mirroring walkdir's loop detection.
+struct Ancestor {
+    path: PathBuf,
+    identity: Option<DirIdentity>,
+    parent: Option<Arc<Ancestor>>,
+}
+
+#[cfg(unix)]
+type DirIdentity = (u64, u64);
+
+#[cfg(not(unix))]
+type DirIdentity = PathBuf;
+
+#[cfg(unix)]
+fn dir_identity(path: &Path) -> Option<DirIdentity> {
+    use std::os::unix::fs::MetadataExt;
+
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.dev(), metadata.ino()))
+}
+
+#[cfg(not(unix))]
+fn dir_identity(path: &Path) -> Option<DirIdentity> {
+    fs::canonicalize(path).ok()
+}
+
+/// Returns `None` when the scan must stop: the sink hung up or a guardrail
+/// refused.
+fn visit_directory(
+    task: &DirTask,
+    shared: &SharedState<'_>,
+    sender: &SyncSender<VacuumRecord>,
+) -> Option<Vec<DirTask>> {
+    let options = shared.options;
+    let root = task.root.path.as_path();
+    let root_value = task.root.value.as_str();
+    let mut children = Vec::new();
+    let mut child_ancestors = None;
+    let ignores = load_ignores(task.ignores.as_ref(), root, &task.dir, options);
+
+    // walkdir checks loops against the directory it was started at; the rest
+    // of the chain lives in `task.ancestors`.
+    for entry in WalkDir::new(&task.dir)
+        .follow_links(options.follow_symlinks)
+        .min_depth(1)
+        .max_depth(1)
+    {
+        let record = match entry {
+            Ok(entry) => {
+                let pruned = shared.pruner.prunes_entry(root, &entry);
+                if ignores_entry(ignores.as_ref(), root, &entry) || (pruned && !options.emit_dirs) {
+                    continue;
+                }
+                if let Err(refusal) = shared.limits.check_depth(root, &entry, task.depth + 1) {
+                    shared.refuse(refusal);
+                    return None;
+                }
+                let escaped = match shared.containment.check(root, &entry) {
+                    Ok(escaped) => escaped,
+                    Err(refusal) => {
+                        shared.refuse(refusal);
+                        return None;
+                    }
+                };
+                let contained =
+                    escaped.is_none() || options.symlink_policy != SymlinkPolicy::WithinRoot;
+                if !entry.file_type().is_dir() || !contained {
+                    build_record(root, root_value, &entry, escaped.as_deref(), options)
+                } else if let Some(device) = task.root.boundary.crossing(&entry) {
+                    task.root.boundary.marker(
+                        root,
+                        root_value,
+                        entry.path(),
+                        device,
+                        escaped.as_deref().map(escape_warning).into_iter().collect(),
+                    )
+                } else if let Some(ancestor) = (options.follow_symlinks && entry.path_is_symlink())
+                    .then(|| find_loop_ancestor(entry.path(), task.ancestors.as_ref()))
+                    .flatten()
+                {
+                    build_skipped_entry(
+                        root,
+                        root_value,
+                        entry.path(),
+                        format!(
+                            "File system loop found: {} points to an ancestor {}",
+                            entry.path().display(),
+                            ancestor.display()
+                        ),
+                    )
+                } else if pruned {
+                    // Only reached with --emit-dirs: the directory keeps its
+                    // own record, but nothing below it is visited.
+                    build_record(root, root_value, &entry, escaped.as_deref(), options)
+                } else {
+                    let ancestors = options.follow_symlinks.then(|| {
+                        Arc::clone(child_ancestors.get_or_insert_with(|| {
+                            Arc::new(Ancestor {
+                                path: task.dir.clone(),
+                                identity: dir_identity(&task.dir),
+                                parent: task.ancestors.clone(),
+                            })
+                        }))
+                    });
+                    let dir_record = (options.emit_dirs || escaped.is_some()).then(|| {
+                        build_record(root, root_value, &entry, escaped.as_deref(), options)
+                    });
+                    children.push(DirTask {
+                        root: Arc::clone(&task.root),
+                        dir: entry.into_path(),
+                        depth: task.depth + 1,
+                        ancestors,
+                        ignores: ignores.clone(),
+                    });
+                    match dir_record {
+                        Some(record) => record,
+                        None => continue,
+                    }
+                }
+            }
+            Err(error) => match build_skipped_from_walk_error(root, root_value, &error) {
+                Some(skipped) => skipped,
+                None => continue,
+            },
+        };
+
+        if let Err(refusal) = shared.limits.count_record(root, &record) {
+            shared.refuse(refusal);
+            return None;
+        }
+        {
+            let mut progress = lock(&shared.progress);
+            progress.record_processed();
+            progress.emit_if_due();
+        }
+        emit_warning_for_skipped(&record, options.progress_enabled);
+        sender.send(record).ok()?;
+    }
+
+    Some(children)
+}
+
+fn find_loop_ancestor(child: &Path, ancestors: Option<&Arc<Ancestor>>) -> Option<PathBuf> {
+    let mut current = ancestors?;
+    let identity = dir_identity(child)?;
+
+    loop {
+        if current.identity.as_ref() == Some(&identity) {
+            return Some(current.path.clone());
+        }
+        current = current.parent.as_ref()?;
+    }
+}
+
+#[derive(Default)]
+struct WorkQueue {
+    state: Mutex<QueueState>,
+    ready: Condvar,
+}
+
+#[derive(Default)]
+struct QueueState {
+    pending: Vec<DirTask>,
+    active: usize,
+    cancelled: bool,
+}
+
+impl WorkQueue {
+    fn push(&self, task: DirTask) {
+        lock(&self.state).pending.push(task);
+    }
+
+    /// Blocks until a directory is available, or returns `None` once the queue
+    /// is drained and no worker can produce more work.
+    fn next(&self) -> Option<DirTask> {
+        let mut state = lock(&self.state);
+        loop {
+            if state.cancelled {
+                return None;
+            }
+            if let Some(task) = state.pending.pop() {
+                state.active += 1;
+                return Some(task);
+            }
+            if state.active == 0 {
+                return None;
+            }
+            state = self
+                .ready
+                .wait(state)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+
+    fn complete(&self, children: Vec<DirTask>) {
+        let mut state = lock(&self.state);
+        state.pending.extend(children);
+        state.active -= 1;
+        self.ready.notify_all();
+    }
+
+    /// Stops handing out directories once the sink has stopped listening or a
+    /// guardrail refused.
+    fn cancel(&self) {
+        let mut state = lock(&self.state);
+        state.cancelled = true;
+        state.pending.clear();
+        self.ready.notify_all();
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf};
+
+    use crate::{
+        output::jsonl::serialize_sorted_jsonl,
+        walk::walker::{ScanOptions, scan_roots_with_options},
+    };
+
+    fn fixture(name: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("fixtures")
+            .join(name)
+    }
+
+    fn scan_jsonl(roots: &[PathBuf], follow_symlinks: bool, threads: usize) -> Vec<String> {
+        let options = ScanOptions {
+            threads,
+            ..ScanOptions::new(follow_symlinks)
+        };
+        let records = scan_roots_with_options(roots, &options).expect("scan should not refuse");
+        serialize_sorted_jsonl(&records)
+    }
+
+    #[test]
+    fn parallel_scan_matches_serial_scan_for_multiple_roots() {
+        let roots = vec![
+            fixture("nested"),
+            fixture("simple"),
+            fixture("deeply_nested"),
+            fixture("large_file_counts"),
+        ];
+
+        assert_eq!(scan_jsonl(&roots, true, 4), scan_jsonl(&roots, true, 1));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn parallel_scan_matches_serial_scan_for_symlinks_in_both_modes() {
+        let roots = vec![fixture("symlinks")];
+
+        assert_eq!(scan_jsonl(&roots, true, 3), scan_jsonl(&roots, true, 1));
+        assert_eq!(scan_jsonl(&roots, false, 3), scan_jsonl(&roots, false, 1));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn parallel_scan_reports_symlink_cycles_like_serial_scan() {
+        use std::os::unix::fs::symlink;
+
+        let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+        let root = temp_dir.path().join("root");
+        let nested = root.join("loop").join("inner");
+        fs::create_dir_all(&nested).expect("nested directory should be created");
+        fs::write(nested.join("payload.txt"), "cycle-safe").expect("payload file should exist");
+        symlink(&root, nested.join("to_root")).expect("root cycle symlink should be created");
+        symlink(root.join("loop"), nested.join("to_parent"))
+            .expect("parent cycle symlink should be created");
+
+        let roots = vec![root];
+        let parallel = scan_jsonl(&roots, true, 4);
+
+        assert_eq!(parallel, scan_jsonl(&roots, true, 1));
+        assert!(
+            parallel
+                .iter()
+                .any(|line| line.contains("File system loop found"))
+        );
+    }
+}
@@ -0,0 +1,206 @@
+use std::{
+    fs,
+    path::{Component, Path, PathBuf},
+};
+
+use clap::ValueEnum;
+use serde_json::json;
+use walkdir::DirEntry;
+
+use crate::{
+    record::{builder::SymlinkInfo, path::native_string},
+    refusal::{codes::RefusalCode, payload::Refusal},
+    walk::walker::{ScanOptions, absolute_root, shell_quote_root},
+};
+
+/// What a followed walk does with a symlink whose canonical target lies
+/// outside every scan root.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum SymlinkPolicy {
+    /// Traverse it and warn with `W_SYMLINK_ESCAPES_ROOT`.
+    #[default]
+    Follow,
+    /// Record the link itself with the warning and never traverse it.
+    WithinRoot,
+    /// Refuse the scan with `E_SYMLINK_ESCAPES_ROOT`.
+    Refuse,
+}
+
+impl SymlinkPolicy {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Follow => "follow",
+            Self::WithinRoot => "within-root",
+            Self::Refuse => "refuse",
+        }
+    }
+}
+
+/// Canonical scan roots for `--symlink-policy`, shared by both walkers.
+pub(crate) struct Containment {
+    policy: SymlinkPolicy,
+    /// Empty under `--no-follow`, where no link is ever traversed.
+    roots: Vec<PathBuf>,
+}
+
+impl Containment {
+    pub(crate) fn new(roots: &[PathBuf], options: &ScanOptions) -> Self {
+        let roots = if options.follow_symlinks {
+            roots
+                .iter()
+                .map(|root| {
+                    let root = absolute_root(root);
+                    fs::canonicalize(&root).unwrap_or(root)
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+        Self {
+            policy: options.symlink_policy,
+            roots,
+        }
+    }
+
+    /// Returns the canonical target when `entry` is a link escaping every
+    /// root. Dangling links cannot be traversed and are never reported here.
+    pub(crate) fn check(&self, root: &Path, entry: &DirEntry) -> Result<Option<PathBuf>, Refusal> {
+        if self.roots.is_empty() || !entry.path_is_symlink() {
+            return Ok(None);
+        }
+        let Ok(resolved) = fs::canonicalize(entry.path()) else {
+            return Ok(None);
+        };
+        if self
+            .roots
+            .iter()
+            .any(|scan_root| resolved.starts_with(scan_root))
+        {
+            return Ok(None);
+        }
+
+        if self.policy == SymlinkPolicy::Refuse {
+            return Err(Refusal::new(
+                RefusalCode::SymlinkEscapesRoot,
+                json!({
+                    "root": root.display().to_string(),
+                    "path": entry.path().display().to_string(),
+                    "target": resolved.display().to_string(),
+                }),
+            )
+            .with_next_command(format!(
+                "vacuum {} --symlink-policy within-root",
+                shell_quote_root(root)
+            )));
+        }
+        Ok(Some(resolved))
+    }
+}
+
+/// Describes the link at `path`, or returns `None` when it is not a symlink.
+/// `escapes_root` compares the fully resolved target against the canonical
+/// root; a dangling link is judged by its lexical target instead.
+pub(crate) fn symlink_info(root: &Path, path: &Path) -> Option<SymlinkInfo> {
+    let target = fs::read_link(path).ok()?;
+    let resolved = fs::canonicalize(path).ok();
+    let canonical_root = fs::canonicalize(root).unwrap_or_else(|_| root.to_path_buf());
+
+    let escapes_root = match &resolved {
+        Some(resolved) => !resolved.starts_with(&canonical_root),
+        None => {
+            let parent = path.parent().unwrap_or(root);
+            let lexical = lexical_join(parent, &target);
+            !lexical.starts_with(root) && !lexical.starts_with(&canonical_root)
+        }
+    };
+
+    Some(SymlinkInfo {
+        target: native_string(&target),
+        resolved: resolved.as_deref().map(native_string),
+        escapes_root,
+    })
+}
+
+/// Joins `target` onto `base` and folds `.` / `..` without touching the
+/// filesystem, the way the kernel would if no component were itself a link.
+pub(crate) fn lexical_join(base: &Path, target: &Path) -> PathBuf {
+    let mut joined = PathBuf::new();
+    for component in base.join(target).components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                joined.pop();
+            }
+            other => joined.push(other),
+        }
+    }
+    joined
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::{fs, os::unix::fs::symlink, path::Path};
+
+    use super::{lexical_join, symlink_info};
+
+    #[test]
+    fn regular_files_have_no_symlink_info() {
+        let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+        let file = temp_dir.path().join("plain.txt");
+        fs::write(&file, "x").expect("file should be written");
+
+        assert!(symlink_info(temp_dir.path(), &file).is_none());
+    }
+
+    #[test]
+    fn links_inside_and_outside_the_root_are_distinguished() {
+        let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+        let root = temp_dir.path().join("root");
+        fs::create_dir_all(root.join("nested")).expect("root should be created");
+        fs::write(root.join("target.txt"), "x").expect("target should be written");
+        fs::write(temp_dir.path().join("secret.txt"), "x").expect("outside file should be written");
+        symlink("../target.txt", root.join("nested/inside")).expect("link should be created");
+        symlink("../secret.txt", root.join("outside")).expect("link should be created");
+
+        let inside = symlink_info(&root, &root.join("nested/inside")).expect("inside is a link");
+        assert_eq!(inside.target, "../target.txt");
+        assert!(!inside.escapes_root);
+        assert!(
+            inside
+                .resolved
+                .as_deref()
+                .is_some_and(|resolved| resolved.ends_with("root/target.txt"))
+        );
+
+        let outside = symlink_info(&root, &root.join("outside")).expect("outside is a link");
+        assert!(outside.escapes_root);
+    }
+
+    #[test]
+    fn dangling_links_are_judged_lexically() {
+        let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+        let root = temp_dir.path().join("root");
+        fs::create_dir_all(&root).expect("root should be created");
+        symlink("missing.txt", root.join("dangling")).expect("link should be created");
+        symlink("/nonexistent/elsewhere", root.join("away")).expect("link should be created");
+
+        let dangling = symlink_info(&root, &root.join("dangling")).expect("dangling is a link");
+        assert_eq!(dangling.resolved, None);
+        assert!(!dangling.escapes_root);
+
+        let away = symlink_info(&root, &root.join("away")).expect("away is a link");
+        assert!(away.escapes_root);
+    }
+
+    #[test]
+    fn lexical_join_folds_parent_components() {
+        assert_eq!(
+            lexical_join(Path::new("/data/a/b"), Path::new("../../c/./d")),
+            Path::new("/data/c/d")
+        );
+        assert_eq!(
+            lexical_join(Path::new("/data"), Path::new("/etc/passwd")),
+            Path::new("/etc/passwd")
+        );
+    }
+}
@@ -1,24 +1,98 @@
 use std::{
     fs, io,
+    ops::ControlFlow,
     path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
     time::{Duration, Instant, SystemTime},
 };
 
 use chrono::{SecondsFormat, Utc};
 use serde_json::json;
-use walkdir::{Error as WalkdirError, WalkDir};
+use walkdir::{DirEntry, Error as WalkdirError, WalkDir};
 
 use crate::{
     record::{
         builder::{VacuumRecord, Warning},
-        mime::guess_from_extension,
+        mime::{guess_from_extension, sniff_file},
         path::{native_string, normalize_relative},
+        posix::{apply_posix, change_time},
     },
     refusal::{codes::RefusalCode, payload::Refusal},
+    walk::{
+        device::DeviceBoundary,
+        filter::DirPruner,
+        ignore::{IgnoreLog, IgnoreStack},
+        parallel::scan_roots_parallel,
+        symlink::{Containment, SymlinkPolicy, symlink_info},
+    },
 };
 
+#[derive(Debug, Clone)]
+pub struct ScanOptions {
+    pub follow_symlinks: bool,
+    pub progress_enabled: bool,
+    pub threads: usize,
+    pub max_depth: Option<usize>,
+    pub max_files: Option<u64>,
+    pub sniff: bool,
+    /// Record mode, ownership, and inode fields.
+    pub posix: bool,
+    /// Resolve `uid` / `gid` to names; only meaningful with `posix`.
+    pub owner_names: bool,
+    /// Record `ctime` and `btime`.
+    pub times: bool,
+    /// Record `atime`.
+    pub atime: bool,
+    /// Emit records for directories below each root.
+    pub emit_dirs: bool,
+    /// Handling of followed links that leave every root.
+    pub symlink_policy: SymlinkPolicy,
+    /// Do not descend into directories on another device than their root.
+    pub one_file_system: bool,
+    /// Skip `.vacuumignore` (and `.gitignore`) handling entirely.
+    pub no_ignore: bool,
+    /// Also honor `.gitignore` files.
+    pub gitignore: bool,
+    /// `--exclude` globs; `P/**` patterns prune matching directories during
+    /// the walk. Callers still filter records with `PathFilter`.
+    pub exclude: Vec<String>,
+    /// Prune with case-insensitive globs, matching `--glob-case-insensitive`.
+    pub glob_case_insensitive: bool,
+    /// Collects the ignore files the walk read, for the witness record.
+    pub ignore_log: Option<Arc<IgnoreLog>>,
+}
+
+impl ScanOptions {
+    pub fn new(follow_symlinks: bool) -> Self {
+        Self {
+            follow_symlinks,
+            progress_enabled: false,
+            threads: 1,
+            max_depth: None,
+            max_files: None,
+            sniff: false,
+            posix: false,
+            owner_names: false,
+            times: false,
+            atime: false,
+            emit_dirs: false,
+            symlink_policy: SymlinkPolicy::Follow,
+            one_file_system: false,
+            no_ignore: false,
+            gitignore: false,
+            exclude: Vec::new(),
+            glob_case_insensitive: false,
+            ignore_log: None,
+        }
+    }
+}
+
 pub fn scan_roots(roots: &[PathBuf], follow_symlinks: bool) -> Vec<VacuumRecord> {
-    scan_roots_with_progress(roots, follow_symlinks, false)
+    // No guardrails are configured, so the scan cannot refuse.
+    scan_roots_with_options(roots, &ScanOptions::new(follow_symlinks)).unwrap_or_default()
 }
 
 pub fn scan_roots_with_progress(
@@ -26,55 +100,215 @@ pub fn scan_roots_with_progress(
     follow_symlinks: bool,
     progress_enabled: bool,
 ) -> Vec<VacuumRecord> {
+    let options = ScanOptions {
+        progress_enabled,
+        ..ScanOptions::new(follow_symlinks)
+    };
+    scan_roots_with_options(roots, &options).unwrap_or_default()
+}
+
+pub fn scan_roots_with_options(
+    roots: &[PathBuf],
+    options: &ScanOptions,
+) -> Result<Vec<VacuumRecord>, Refusal> {
     let mut records = Vec::new();
+    scan_roots_into(roots, options, |record| {
+        records.push(record);
+        ControlFlow::Continue(())
+    })?;
+    Ok(records)
+}
+
+/// Hands every record to `sink` as it is discovered, in walk order. The sink
+/// can stop the scan early by returning `ControlFlow::Break`; crossing a
+/// `--max-depth` or `--max-files` guardrail stops it with a refusal. Entries
+/// matched by an ignore file, and directories pruned by an `--exclude 'P/**'`
+/// glob, are dropped before either guardrail sees them. Under
+/// `--one-file-system`, a mount point yields one `_skipped` marker record.
+pub fn scan_roots_into<F>(
+    roots: &[PathBuf],
+    options: &ScanOptions,
+    mut sink: F,
+) -> Result<(), Refusal>
+where
+    F: FnMut(VacuumRecord) -> ControlFlow<()>,
+{
+    if options.threads > 1 {
+        return scan_roots_parallel(roots, options, sink);
+    }
+
+    let follow_symlinks = options.follow_symlinks;
+    let progress_enabled = options.progress_enabled;
+    let limits = ScanLimits::new(options);
+    let pruner = DirPruner::new(&options.exclude, options.glob_case_insensitive);
+    let containment = Containment::new(roots, options);
     let mut progress = ProgressReporter::new(progress_enabled);
+    let mut outcome = Ok(());
 
-    for root in roots {
+    'roots: for root in roots {
         let absolute_root = absolute_root(root);
         let root_value = native_string(&absolute_root);
+        let boundary = DeviceBoundary::new(&absolute_root, options);
+
+        let mut walker = WalkDir::new(&absolute_root).follow_links(follow_symlinks);
+        if let Some(max_depth) = options.max_depth {
+            // Read one level past the limit so deeper content refuses instead
+            // of vanishing from the manifest.
+            walker = walker.max_depth(max_depth.saturating_add(1));
+        }
 
-        for entry in WalkDir::new(&absolute_root)
-            .follow_links(follow_symlinks)
-            .into_iter()
-        {
-            match entry {
+        let mut ignores = IgnoreStack::new(&absolute_root, options);
+        // With --emit-dirs a pruned directory still reaches the loop below,
+        // which emits its own record and skips its children.
+        let mut entries = walker.into_iter().filter_entry(|entry| {
+            (options.emit_dirs || !pruner.prunes_entry(&absolute_root, entry))
+                && ignores.admit(entry)
+        });
+        while let Some(entry) = entries.next() {
+            let record = match entry {
                 Ok(entry) => {
-                    if entry.depth() == 0 || entry.file_type().is_dir() {
+                    if entry.depth() == 0 {
                         continue;
                     }
+                    if let Err(refusal) = limits.check_depth(&absolute_root, &entry, entry.depth())
+                    {
+                        outcome = Err(refusal);
+                        break 'roots;
+                    }
+                    let escaped = match containment.check(&absolute_root, &entry) {
+                        Ok(escaped) => escaped,
+                        Err(refusal) => {
+                            outcome = Err(refusal);
+                            break 'roots;
+                        }
+                    };
+                    let contained =
+                        escaped.is_none() || options.symlink_policy != SymlinkPolicy::WithinRoot;
+                    let crossing = contained.then(|| boundary.crossing(&entry)).flatten();
+                    if entry.file_type().is_dir() {
+                        if !contained
+                            || crossing.is_some()
+                            || pruner.prunes_entry(&absolute_root, &entry)
+                        {
+                            entries.skip_current_dir();
+                        } else if !options.emit_dirs && escaped.is_none() {
+                            continue;
+                        }
+                    }
 
-                    records.push(build_record(
-                        &absolute_root,
-                        &root_value,
-                        entry.path(),
-                        follow_symlinks,
-                    ));
-                    progress.record_processed();
-                    progress.emit_if_due();
-
-                    if let Some(last_record) = records.last() {
-                        emit_warning_for_skipped(last_record, progress_enabled);
+                    match crossing {
+                        Some(device) => boundary.marker(
+                            &absolute_root,
+                            &root_value,
+                            entry.path(),
+                            device,
+                            escaped.as_deref().map(escape_warning).into_iter().collect(),
+                        ),
+                        None => build_record(
+                            &absolute_root,
+                            &root_value,
+                            &entry,
+                            escaped.as_deref(),
+                            options,
+                        ),
                     }
                 }
                 Err(error) => {
-                    if let Some(skipped) =
-                        build_skipped_from_walk_error(&absolute_root, &root_value, &error)
-                    {
-                        records.push(skipped);
-                        progress.record_processed();
-                        progress.emit_if_due();
-
-                        if let Some(last_record) = records.last() {
-                            emit_warning_for_skipped(last_record, progress_enabled);
-                        }
+                    match build_skipped_from_walk_error(&absolute_root, &root_value, &error) {
+                        Some(skipped) => skipped,
+                        None => continue,
                     }
                 }
+            };
+
+            if let Err(refusal) = limits.count_record(&absolute_root, &record) {
+                outcome = Err(refusal);
+                break 'roots;
+            }
+            progress.record_processed();
+            progress.emit_if_due();
+            emit_warning_for_skipped(&record, progress_enabled);
+
+            if sink(record).is_break() {
+                break 'roots;
             }
         }
     }
 
     progress.emit_final();
-    records
+    outcome
+}
+
+/// `--max-depth` / `--max-files` guardrails shared by the serial and parallel walkers.
+pub(crate) struct ScanLimits {
+    max_depth: Option<usize>,
+    max_files: Option<u64>,
+    emit_dirs: bool,
+    files_seen: AtomicU64,
+}
+
+impl ScanLimits {
+    pub(crate) fn new(options: &ScanOptions) -> Self {
+        Self {
+            max_depth: options.max_depth,
+            max_files: options.max_files,
+            emit_dirs: options.emit_dirs,
+            files_seen: AtomicU64::new(0),
+        }
+    }
+
+    /// Refuses an entry below `--max-depth` that would be emitted or descended
+    /// into. An empty directory there leaves no trace without `--emit-dirs`,
+    /// so it passes.
+    pub(crate) fn check_depth(
+        &self,
+        root: &Path,
+        entry: &DirEntry,
+        depth: usize,
+    ) -> Result<(), Refusal> {
+        match self.max_depth {
+            Some(max_depth) if depth > max_depth && !self.leaves_no_trace(entry) => {
+                let directory = entry.path().parent().unwrap_or(root);
+                Err(Refusal::new(
+                    RefusalCode::TooDeep,
+                    json!({
+                        "root": root.display().to_string(),
+                        "path": directory.display().to_string(),
+                        "max_depth": max_depth,
+                    }),
+                )
+                .with_next_command(format!("ls -la {}", shell_quote_root(directory))))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn leaves_no_trace(&self, entry: &DirEntry) -> bool {
+        !self.emit_dirs
+            && entry.file_type().is_dir()
+            && fs::read_dir(entry.path()).is_ok_and(|mut children| children.next().is_none())
+    }
+
+    /// Counts every discovered file, before `--include` / `--exclude`.
+    /// Directory records and `--one-file-system` markers are not files.
+    pub(crate) fn count_record(&self, root: &Path, record: &VacuumRecord) -> Result<(), Refusal> {
+        if record.file_type.as_deref() == Some("dir") {
+            return Ok(());
+        }
+        let seen = self.files_seen.fetch_add(1, Ordering::Relaxed) + 1;
+        match self.max_files {
+            Some(max_files) if seen > max_files => Err(Refusal::new(
+                RefusalCode::TooManyFiles,
+                json!({
+                    "root": root.display().to_string(),
+                    "max_files": max_files,
+                    "files_seen": seen,
+                }),
+            )
+            .with_next_command(format!("ls -la {}", shell_quote_root(root)))),
+            _ => Ok(()),
+        }
+    }
 }
 
 pub fn validate_roots(roots: &[PathBuf]) -> Result<(), Refusal> {
@@ -91,12 +325,19 @@ pub fn validate_roots(roots: &[PathBuf]) -> Result<(), Refusal> {
     Ok(())
 }
 
-fn build_record(
+/// `escaped` is the canonical target of a link that leaves every root (see
+/// `Containment`); such records carry a `W_SYMLINK_ESCAPES_ROOT` warning and,
+/// under `--symlink-policy within-root`, describe the link instead of its target.
+pub(crate) fn build_record(
     root: &Path,
     root_value: &str,
-    entry_path: &Path,
-    follow_symlinks: bool,
+    entry: &DirEntry,
+    escaped: Option<&Path>,
+    options: &ScanOptions,
 ) -> VacuumRecord {
+    let entry_path = entry.path();
+    let follow_symlinks = options.follow_symlinks
+        && !(escaped.is_some() && options.symlink_policy == SymlinkPolicy::WithinRoot);
     let relative_path = match entry_path.strip_prefix(root) {
         Ok(relative) => normalize_relative(relative),
         Err(_) => normalize_relative(entry_path),
@@ -115,6 +356,9 @@ fn build_record(
     record.root = root_value.to_string();
     record.extension = extension;
     record.mime_guess = mime_guess;
+    if entry.path_is_symlink() {
+        record.symlink = symlink_info(root, entry_path);
+    }
 
     let metadata = if follow_symlinks {
         fs::metadata(entry_path)
@@ -130,10 +374,44 @@ fn build_record(
                 entry_path.to_path_buf()
             };
             record.path = native_string(&output_path);
-            record.size = Some(metadata.len());
-            record.mtime = format_mtime(metadata.modified().ok());
+            record.file_type = file_type_name(metadata.file_type()).map(str::to_string);
+            if metadata.is_dir() {
+                // Directory sizes and dotted names are filesystem trivia, not
+                // content; keep them out of the manifest.
+                record.extension = None;
+                record.mime_guess = None;
+            } else {
+                record.size = Some(metadata.len());
+            }
+            record.mtime = format_timestamp(metadata.modified().ok());
+            if options.times {
+                record.ctime = format_timestamp(change_time(&metadata));
+                record.btime = Some(format_timestamp(metadata.created().ok()));
+            }
+            if options.atime {
+                record.atime = format_timestamp(metadata.accessed().ok());
+            }
+            if options.posix {
+                apply_posix(&mut record, &metadata, options.owner_names);
+            }
+            if options.sniff && metadata.is_file() {
+                match sniff_file(entry_path) {
+                    Ok(sniffed) => record.mime_sniffed = sniffed.map(str::to_string),
+                    Err(error) => {
+                        record._warnings = Some(vec![io_warning(
+                            format!("Cannot sniff file contents: {error}"),
+                            error.to_string(),
+                        )]);
+                    }
+                }
+            }
         }
         Err(error) => {
+            // A dangling symlink still knows it is a symlink.
+            record.file_type = fs::symlink_metadata(entry_path)
+                .ok()
+                .and_then(|metadata| file_type_name(metadata.file_type()))
+                .map(str::to_string);
             record._skipped = Some(true);
             record._warnings = Some(vec![io_warning(
                 format!("Cannot read file metadata: {error}"),
@@ -142,16 +420,70 @@ fn build_record(
         }
     }
 
+    if let Some(target) = escaped {
+        record
+            ._warnings
+            .get_or_insert_with(Vec::new)
+            .push(escape_warning(target));
+    }
+
     record
 }
 
-fn format_mtime(value: Option<SystemTime>) -> Option<String> {
-    value.map(|mtime| {
-        chrono::DateTime::<Utc>::from(mtime).to_rfc3339_opts(SecondsFormat::Millis, true)
+/// The warning on a followed link whose canonical `target` leaves every root.
+pub(crate) fn escape_warning(target: &Path) -> Warning {
+    Warning {
+        tool: "vacuum".to_string(),
+        code: "W_SYMLINK_ESCAPES_ROOT".to_string(),
+        message: "Symlink target is outside every scan root".to_string(),
+        detail: json!({ "target": native_string(target) }),
+    }
+}
+
+#[cfg(unix)]
+fn file_type_name(file_type: fs::FileType) -> Option<&'static str> {
+    use std::os::unix::fs::FileTypeExt;
+
+    if file_type.is_file() {
+        Some("file")
+    } else if file_type.is_dir() {
+        Some("dir")
+    } else if file_type.is_symlink() {
+        Some("symlink")
+    } else if file_type.is_fifo() {
+        Some("fifo")
+    } else if file_type.is_socket() {
+        Some("socket")
+    } else if file_type.is_block_device() {
+        Some("block")
+    } else if file_type.is_char_device() {
+        Some("char")
+    } else {
+        None
+    }
+}
+
+#[cfg(not(unix))]
+fn file_type_name(file_type: fs::FileType) -> Option<&'static str> {
+    if file_type.is_file() {
+        Some("file")
+    } else if file_type.is_dir() {
+        Some("dir")
+    } else if file_type.is_symlink() {
+        Some("symlink")
+    } else {
+        None
+    }
+}
+
+/// RFC 3339 UTC with millisecond precision, shared by every timestamp field.
+fn format_timestamp(value: Option<SystemTime>) -> Option<String> {
+    value.map(|time| {
+        chrono::DateTime::<Utc>::from(time).to_rfc3339_opts(SecondsFormat::Millis, true)
     })
 }
 
-fn build_skipped_from_walk_error(
+pub(crate) fn build_skipped_from_walk_error(
     root: &Path,
     root_value: &str,
     error: &WalkdirError,
@@ -161,6 +493,20 @@ fn build_skipped_from_walk_error(
         return None;
     }
 
+    Some(build_skipped_entry(
+        root,
+        root_value,
+        path,
+        error.to_string(),
+    ))
+}
+
+pub(crate) fn build_skipped_entry(
+    root: &Path,
+    root_value: &str,
+    path: &Path,
+    error: String,
+) -> VacuumRecord {
     let relative_path = match path.strip_prefix(root) {
         Ok(relative) => normalize_relative(relative),
         Err(_) => normalize_relative(path),
@@ -177,16 +523,17 @@ fn build_skipped_from_walk_error(
     record.root = root_value.to_string();
     record.extension = extension;
     record.mime_guess = mime_guess;
+    record.symlink = symlink_info(root, path);
     record._skipped = Some(true);
     record._warnings = Some(vec![io_warning(
         format!("Cannot read directory entry: {error}"),
-        error.to_string(),
+        error,
     )]);
 
-    Some(record)
+    record
 }
 
-fn io_warning(message: String, error: String) -> Warning {
+pub(crate) fn io_warning(message: String, error: String) -> Warning {
     Warning {
         tool: "vacuum".to_string(),
         code: "E_IO".to_string(),
@@ -195,7 +542,7 @@ fn io_warning(message: String, error: String) -> Warning {
     }
 }
 
-fn emit_warning_for_skipped(record: &VacuumRecord, progress_enabled: bool) {
+pub(crate) fn emit_warning_for_skipped(record: &VacuumRecord, progress_enabled: bool) {
     if record._skipped != Some(true) {
         return;
     }
@@ -222,7 +569,7 @@ fn emit_warning_for_skipped(record: &VacuumRecord, progress_enabled: bool) {
     }
 }
 
-struct ProgressReporter {
+pub(crate) struct ProgressReporter {
     enabled: bool,
     processed: u64,
     started_at: Instant,
@@ -230,7 +577,7 @@ struct ProgressReporter {
 }
 
 impl ProgressReporter {
-    fn new(enabled: bool) -> Self {
+    pub(crate) fn new(enabled: bool) -> Self {
         let now = Instant::now();
         Self {
             enabled,
@@ -240,11 +587,11 @@ impl ProgressReporter {
         }
     }
 
-    fn record_processed(&mut self) {
+    pub(crate) fn record_processed(&mut self) {
         self.processed = self.processed.saturating_add(1);
     }
 
-    fn emit_if_due(&mut self) {
+    pub(crate) fn emit_if_due(&mut self) {
         if !self.enabled {
             return;
         }
@@ -255,7 +602,7 @@ impl ProgressReporter {
         }
     }
 
-    fn emit_final(&mut self) {
+    pub(crate) fn emit_final(&mut self) {
         if !self.enabled {
             return;
         }
@@ -276,7 +623,7 @@ impl ProgressReporter {
     }
 }
 
-fn absolute_root(root: &Path) -> PathBuf {
+pub(crate) fn absolute_root(root: &Path) -> PathBuf {
     if root.is_absolute() {
         return root.to_path_buf();
     }
@@ -328,7 +675,7 @@ fn display_root(root: &Path) -> String {
     }
 }
 
-fn shell_quote_root(root: &Path) -> String {
+pub(crate) fn shell_quote_root(root: &Path) -> String {
     format!("'{}'", display_root(root).replace('\'', "'\"'\"'"))
 }
 
@@ -370,7 +717,9 @@ mod tests {
         path::{Path, PathBuf},
     };
 
-    use super::{scan_roots, suggested_scan_root, validate_roots};
+    use super::{
+        ScanOptions, scan_roots, scan_roots_with_options, suggested_scan_root, validate_roots,
+    };
     use crate::refusal::codes::RefusalCode;
 
     fn fixture(name: &str) -> PathBuf {
@@ -415,6 +764,294 @@ mod tests {
         );
     }
 
+    #[test]
+    fn max_depth_allows_scans_within_the_limit() {
+        let options = ScanOptions {
+            max_depth: Some(3),
+            ..ScanOptions::new(true)
+        };
+        let records = scan_roots_with_options(&[fixture("nested")], &options)
+            .expect("nested fixture is three levels deep");
+
+        assert_eq!(records.len(), 3);
+    }
+
+    #[test]
+    fn max_depth_refuses_deeper_entries_in_serial_and_parallel_walks() {
+        for threads in [1, 4] {
+            let options = ScanOptions {
+                threads,
+                max_depth: Some(2),
+                ..ScanOptions::new(true)
+            };
+            let refusal = scan_roots_with_options(&[fixture("nested")], &options)
+                .expect_err("leaf.yaml sits below --max-depth 2");
+
+            assert_eq!(refusal.code, RefusalCode::TooDeep);
+            assert_eq!(refusal.detail["max_depth"], 2);
+            let deep_dir = fixture("nested").join("region").join("deep");
+            assert_eq!(refusal.detail["path"].as_str(), deep_dir.to_str());
+            let expected = format!("ls -la '{}'", deep_dir.display());
+            assert_eq!(refusal.next_command.as_deref(), Some(expected.as_str()));
+        }
+    }
+
+    #[test]
+    fn empty_directories_past_max_depth_do_not_refuse() {
+        let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("a/b/empty")).expect("dirs should be created");
+        fs::write(root.join("a/data.csv"), "x").expect("file should be written");
+
+        for threads in [1, 4] {
+            let options = ScanOptions {
+                threads,
+                max_depth: Some(2),
+                ..ScanOptions::new(true)
+            };
+            let records = scan_roots_with_options(&[root.to_path_buf()], &options)
+                .expect("a/b/empty holds nothing to record");
+            assert_eq!(records.len(), 1);
+
+            let options = ScanOptions {
+                emit_dirs: true,
+                ..options
+            };
+            let refusal = scan_roots_with_options(&[root.to_path_buf()], &options)
+                .expect_err("--emit-dirs would record a/b/empty");
+            assert_eq!(refusal.code, RefusalCode::TooDeep);
+        }
+    }
+
+    #[test]
+    fn max_files_refuses_once_the_count_is_exceeded() {
+        for threads in [1, 4] {
+            let options = ScanOptions {
+                threads,
+                max_files: Some(10),
+                ..ScanOptions::new(true)
+            };
+            let refusal = scan_roots_with_options(&[fixture("large_file_counts")], &options)
+                .expect_err("fixture holds more than ten files");
+
+            assert_eq!(refusal.code, RefusalCode::TooManyFiles);
+            assert_eq!(refusal.detail["max_files"], 10);
+            assert_eq!(refusal.detail["files_seen"], 11);
+        }
+
+        for threads in [1, 4] {
+            let options = ScanOptions {
+                threads,
+                max_files: Some(3),
+                emit_dirs: true,
+                ..ScanOptions::new(true)
+            };
+            let records = scan_roots_with_options(&[fixture("nested")], &options)
+                .expect("directory records do not count as files");
+            assert!(records.len() > 3);
+        }
+    }
+
+    #[test]
+    fn ignored_subtrees_are_pruned_before_guardrails_in_both_walkers() {
+        let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+        let root = temp_dir.path().join("root");
+        fs::create_dir_all(root.join("node_modules/pkg/lib/deep"))
+            .expect("ignored tree should be created");
+        fs::create_dir_all(root.join("src")).expect("source dir should be created");
+        fs::write(root.join("node_modules/pkg/lib/deep/index.js"), "x")
+            .expect("ignored file should be written");
+        fs::write(root.join("src/main.rs"), "fn main() {}").expect("file should be written");
+        fs::write(root.join(".vacuumignore"), "node_modules/\n")
+            .expect("ignore file should be written");
+
+        for threads in [1, 4] {
+            let options = ScanOptions {
+                threads,
+                max_depth: Some(2),
+                ..ScanOptions::new(true)
+            };
+            let records = scan_roots_with_options(std::slice::from_ref(&root), &options)
+                .expect("ignored directories are never descended");
+            let mut relative_paths = records
+                .iter()
+                .map(|record| record.relative_path.as_str())
+                .collect::<Vec<_>>();
+            relative_paths.sort();
+
+            assert_eq!(relative_paths, vec![".vacuumignore", "src/main.rs"]);
+        }
+
+        let options = ScanOptions {
+            no_ignore: true,
+            ..ScanOptions::new(true)
+        };
+        let records = scan_roots_with_options(std::slice::from_ref(&root), &options)
+            .expect("scan should not refuse");
+        assert_eq!(records.len(), 3);
+    }
+
+    #[test]
+    fn excluded_directories_are_never_descended() {
+        let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+        let root = temp_dir.path().join("root");
+        fs::create_dir_all(root.join("archive/2019/q1/raw")).expect("archive should be created");
+        fs::write(root.join("archive/2019/q1/raw/old.csv"), "id\n")
+            .expect("file should be written");
+        fs::write(root.join("current.csv"), "id\n").expect("file should be written");
+
+        for threads in [1, 4] {
+            let options = ScanOptions {
+                threads,
+                max_depth: Some(1),
+                exclude: vec!["archive/**".to_string()],
+                ..ScanOptions::new(true)
+            };
+            let records = scan_roots_with_options(std::slice::from_ref(&root), &options)
+                .expect("pruned archive never reaches the depth guardrail");
+
+            assert_eq!(records.len(), 1);
+            assert_eq!(records[0].relative_path, "current.csv");
+        }
+    }
+
+    #[test]
+    fn pruned_directories_keep_their_own_record_under_emit_dirs() {
+        let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+        let root = temp_dir.path().join("root");
+        fs::create_dir_all(root.join("archive/2019")).expect("archive should be created");
+        fs::write(root.join("archive/2019/old.csv"), "id\n").expect("file should be written");
+        fs::write(root.join("current.csv"), "id\n").expect("file should be written");
+
+        for threads in [1, 4] {
+            let options = ScanOptions {
+                threads,
+                emit_dirs: true,
+                exclude: vec!["archive/**".to_string()],
+                ..ScanOptions::new(true)
+            };
+            let records = scan_roots_with_options(std::slice::from_ref(&root), &options)
+                .expect("scan should not refuse");
+            let mut relative_paths = records
+                .iter()
+                .map(|record| record.relative_path.as_str())
+                .collect::<Vec<_>>();
+            relative_paths.sort();
+
+            assert_eq!(relative_paths, vec!["archive", "current.csv"]);
+        }
+    }
+
+    #[test]
+    fn emit_dirs_adds_directory_records_in_both_walkers() {
+        for threads in [1, 4] {
+            let options = ScanOptions {
+                threads,
+                emit_dirs: true,
+                ..ScanOptions::new(true)
+            };
+            let records = scan_roots_with_options(&[fixture("empty_dirs")], &options)
+                .expect("scan should not refuse");
+            let mut dirs = records
+                .iter()
+                .filter(|record| record.file_type.as_deref() == Some("dir"))
+                .map(|record| (record.relative_path.as_str(), record.size))
+                .collect::<Vec<_>>();
+            dirs.sort();
+
+            assert_eq!(dirs, vec![("a", None), ("b", None), ("b/c", None)]);
+        }
+
+        let records = scan_roots(&[fixture("empty_dirs")], true);
+        assert!(
+            records
+                .iter()
+                .all(|record| record.file_type.as_deref() == Some("file"))
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlink_policy_controls_links_that_leave_every_root() {
+        use std::os::unix::fs::symlink;
+
+        use crate::walk::symlink::SymlinkPolicy;
+
+        let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+        let root = temp_dir.path().join("delivery");
+        let outside = temp_dir.path().join("outside");
+        fs::create_dir_all(&root).expect("root should be created");
+        fs::create_dir_all(&outside).expect("outside dir should be created");
+        fs::write(outside.join("passwd"), "x").expect("outside file should be written");
+        fs::write(root.join("tape.csv"), "id\n").expect("file should be written");
+        symlink(&outside, root.join("data")).expect("dir link should be created");
+        symlink("tape.csv", root.join("alias.csv")).expect("inside link should be created");
+
+        let scan = |policy, threads| {
+            let options = ScanOptions {
+                threads,
+                symlink_policy: policy,
+                ..ScanOptions::new(true)
+            };
+            scan_roots_with_options(std::slice::from_ref(&root), &options).map(|records| {
+                let mut summary = records
+                    .into_iter()
+                    .map(|record| {
+                        let codes = record
+                            ._warnings
+                            .unwrap_or_default()
+                            .into_iter()
+                            .map(|warning| warning.code)
+                            .collect::<Vec<_>>();
+                        (record.relative_path, record.file_type, codes)
+                    })
+                    .collect::<Vec<_>>();
+                summary.sort();
+                summary
+            })
+        };
+        let escape = vec!["W_SYMLINK_ESCAPES_ROOT".to_string()];
+        let file = Some("file".to_string());
+
+        for threads in [1, 4] {
+            assert_eq!(
+                scan(SymlinkPolicy::Follow, threads).expect("follow never refuses"),
+                vec![
+                    ("alias.csv".to_string(), file.clone(), vec![]),
+                    ("data".to_string(), Some("dir".to_string()), escape.clone()),
+                    ("data/passwd".to_string(), file.clone(), vec![]),
+                    ("tape.csv".to_string(), file.clone(), vec![]),
+                ]
+            );
+            assert_eq!(
+                scan(SymlinkPolicy::WithinRoot, threads).expect("within-root never refuses"),
+                vec![
+                    ("alias.csv".to_string(), file.clone(), vec![]),
+                    (
+                        "data".to_string(),
+                        Some("symlink".to_string()),
+                        escape.clone()
+                    ),
+                    ("tape.csv".to_string(), file.clone(), vec![]),
+                ]
+            );
+
+            let refusal = scan(SymlinkPolicy::Refuse, threads).expect_err("data leaves the root");
+            assert_eq!(refusal.code, RefusalCode::SymlinkEscapesRoot);
+            assert_eq!(refusal.detail["path"].as_str(), root.join("data").to_str());
+        }
+
+        let options = ScanOptions {
+            symlink_policy: SymlinkPolicy::Refuse,
+            ..ScanOptions::new(true)
+        };
+        let roots = vec![root.clone(), outside.clone()];
+        assert!(
+            scan_roots_with_options(&roots, &options).is_ok(),
+            "a target inside another scan root is contained"
+        );
+    }
+
     #[cfg(unix)]
     #[test]
     fn follows_directory_symlinks_by_default() {
@@ -0,0 +1,314 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    io::ErrorKind,
+    path::Path,
+};
+
+use serde::Serialize;
+use serde_json::{Value, json};
+
+use crate::witness::ledger::{ensure_ledger_migrated, resolve_ledger_path};
+
+const BROKEN_CHAIN_EXIT: u8 = 1;
+
+/// Checks every ledger line: ids must match a recomputation over the record
+/// with `id` blanked (as `WitnessRecord::compute_id` does), and each `prev`
+/// must name the record on the preceding line.
+pub fn run_verify(json_mode: bool) -> u8 {
+    if let Err(error) = ensure_ledger_migrated() {
+        eprintln!("vacuum: witness read failed: {error}");
+        return crate::cli::exit::REFUSAL;
+    }
+    let path = resolve_ledger_path();
+    let contents = match fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(error) if error.kind() == ErrorKind::NotFound => String::new(),
+        Err(error) => {
+            eprintln!("vacuum: witness read failed: {error}");
+            return crate::cli::exit::REFUSAL;
+        }
+    };
+
+    let report = verify_chain(&path, &contents);
+    if json_mode {
+        println!(
+            "{}",
+            serde_json::to_string(&report).unwrap_or_else(|_| "{}".to_string())
+        );
+    } else {
+        println!("{} records={}", report.verdict, report.records);
+        for finding in &report.findings {
+            println!("line={} kind={}", finding.line, finding.kind);
+        }
+    }
+
+    if report.findings.is_empty() {
+        crate::cli::exit::SCAN_COMPLETE
+    } else {
+        BROKEN_CHAIN_EXIT
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ChainReport {
+    pub ledger: String,
+    pub verdict: &'static str,
+    pub records: usize,
+    pub findings: Vec<Finding>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Finding {
+    pub line: usize,
+    pub kind: &'static str,
+    pub detail: Value,
+}
+
+struct Entry {
+    line: usize,
+    id: Option<String>,
+    prev: Option<String>,
+}
+
+pub fn verify_chain(path: &Path, contents: &str) -> ChainReport {
+    let mut findings = Vec::new();
+    let mut entries = Vec::new();
+
+    for (index, line) in contents.lines().enumerate() {
+        let line_number = index + 1;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let value = match serde_json::from_str::<Value>(line) {
+            Ok(value) if value.is_object() => value,
+            Ok(_) => {
+                findings.push(finding(
+                    line_number,
+                    "unparseable",
+                    json!({ "error": "not a JSON object" }),
+                ));
+                continue;
+            }
+            Err(error) => {
+                findings.push(finding(
+                    line_number,
+                    "unparseable",
+                    json!({ "error": error.to_string() }),
+                ));
+                continue;
+            }
+        };
+
+        let id = value.get("id").and_then(Value::as_str).map(str::to_owned);
+        let expected = recompute_id(&value);
+        if id.as_deref() != Some(expected.as_str()) {
+            findings.push(finding(
+                line_number,
+                "id_mismatch",
+                json!({ "id": id, "expected": expected }),
+            ));
+        }
+        entries.push(Entry {
+            line: line_number,
+            id,
+            prev: value.get("prev").and_then(Value::as_str).map(str::to_owned),
+        });
+    }
+
+    findings.extend(link_findings(&entries));
+    findings.sort_by_key(|finding| finding.line);
+
+    ChainReport {
+        ledger: path.display().to_string(),
+        verdict: if findings.is_empty() {
+            "INTACT"
+        } else {
+            "BROKEN"
+        },
+        records: entries.len(),
+        findings,
+    }
+}
+
+/// Classifies every `prev` that does not name the preceding record:
+/// `duplicate` repeats an earlier id, `fork` shares its parent with an earlier
+/// record, `reordered` points at a record that is out of place, and `gap`
+/// points at a record that is not in the ledger (or restarts the chain).
+fn link_findings(entries: &[Entry]) -> Vec<Finding> {
+    let positions = entries
+        .iter()
+        .enumerate()
+        .filter_map(|(position, entry)| Some((entry.id.as_deref()?, position)))
+        .fold(BTreeMap::new(), |mut positions, (id, position)| {
+            positions.entry(id).or_insert(position);
+            positions
+        });
+    let mut seen_ids = BTreeSet::new();
+    let mut claimed_parents = BTreeSet::new();
+    let mut findings = Vec::new();
+
+    for (position, entry) in entries.iter().enumerate() {
+        if let Some(id) = entry.id.as_deref()
+            && !seen_ids.insert(id)
+        {
+            findings.push(finding(entry.line, "duplicate", json!({ "id": id })));
+            continue;
+        }
+
+        let expected = position
+            .checked_sub(1)
+            .and_then(|previous| entries[previous].id.as_deref());
+        let prev = entry.prev.as_deref();
+        let parent_shared = prev.is_some_and(|prev| !claimed_parents.insert(prev));
+        if prev == expected && !parent_shared {
+            continue;
+        }
+
+        let kind = match prev.and_then(|prev| positions.get(prev)) {
+            _ if parent_shared => "fork",
+            Some(_) => "reordered",
+            None => "gap",
+        };
+        findings.push(finding(
+            entry.line,
+            kind,
+            json!({ "prev": prev, "expected_prev": expected }),
+        ));
+    }
+
+    findings
+}
+
+fn recompute_id(value: &Value) -> String {
+    let mut blanked = value.clone();
+    if let Some(object) = blanked.as_object_mut() {
+        object.insert("id".to_owned(), Value::String(String::new()));
+    }
+    let canonical = serde_json::to_string(&blanked).unwrap_or_default();
+    format!("blake3:{}", blake3::hash(canonical.as_bytes()).to_hex())
+}
+
+fn finding(line: usize, kind: &'static str, detail: Value) -> Finding {
+    Finding { line, kind, detail }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use crate::witness::record::{WitnessRecord, canonical_json};
+
+    use super::verify_chain;
+
+    fn record(prev: Option<String>, outcome: &str) -> WitnessRecord {
+        let mut record = WitnessRecord::from_run(
+            &[PathBuf::from("/data")],
+            serde_json::json!({ "roots": ["/data"] }),
+            outcome,
+            0,
+            "blake3:00".to_string(),
+            prev,
+        );
+        record.compute_id();
+        record
+    }
+
+    fn chain(length: usize) -> Vec<WitnessRecord> {
+        let mut records: Vec<WitnessRecord> = Vec::new();
+        for index in 0..length {
+            let prev = records.last().map(|record| record.id.clone());
+            records.push(record(prev, &format!("RUN_{index}")));
+        }
+        records
+    }
+
+    fn ledger(records: &[&WitnessRecord]) -> String {
+        records
+            .iter()
+            .map(|record| canonical_json(record) + "\n")
+            .collect()
+    }
+
+    fn kinds(contents: &str) -> Vec<(usize, &'static str)> {
+        verify_chain(Path::new("witness.jsonl"), contents)
+            .findings
+            .into_iter()
+            .map(|finding| (finding.line, finding.kind))
+            .collect()
+    }
+
+    #[test]
+    fn intact_chain_has_no_findings() {
+        let records = chain(3);
+        let report = verify_chain(
+            Path::new("witness.jsonl"),
+            &ledger(&records.iter().collect::<Vec<_>>()),
+        );
+
+        assert_eq!(report.verdict, "INTACT");
+        assert_eq!(report.records, 3);
+        assert!(report.findings.is_empty());
+    }
+
+    #[test]
+    fn tampered_record_fails_id_recomputation() {
+        let records = chain(2);
+        let contents = ledger(&[&records[0], &records[1]]).replace("RUN_1", "RUN_X");
+
+        assert_eq!(kinds(&contents), vec![(2, "id_mismatch")]);
+    }
+
+    #[test]
+    fn unparseable_lines_are_reported_not_dropped() {
+        let records = chain(2);
+        let contents = format!(
+            "{}{{truncated\n{}",
+            ledger(&[&records[0]]),
+            ledger(&[&records[1]])
+        );
+
+        assert_eq!(kinds(&contents), vec![(2, "unparseable")]);
+    }
+
+    #[test]
+    fn deleted_record_leaves_a_gap() {
+        let records = chain(3);
+
+        assert_eq!(
+            kinds(&ledger(&[&records[0], &records[2]])),
+            vec![(2, "gap")]
+        );
+    }
+
+    #[test]
+    fn swapped_records_are_reordered() {
+        let records = chain(3);
+
+        assert_eq!(
+            kinds(&ledger(&[&records[0], &records[2], &records[1]])),
+            vec![(2, "reordered"), (3, "reordered")]
+        );
+    }
+
+    #[test]
+    fn concurrent_appends_sharing_a_parent_fork() {
+        let records = chain(2);
+        let sibling = record(Some(records[0].id.clone()), "SIBLING");
+
+        assert_eq!(
+            kinds(&ledger(&[&records[0], &records[1], &sibling])),
+            vec![(3, "fork")]
+        );
+    }
+
+    #[test]
+    fn repeated_line_is_a_duplicate() {
+        let records = chain(2);
+
+        assert_eq!(
+            kinds(&ledger(&[&records[0], &records[1], &records[1]])),
+            vec![(3, "duplicate")]
+        );
+    }
+}
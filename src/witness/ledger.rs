@@ -1,16 +1,33 @@
 use std::{
     env,
     ffi::OsString,
-    fs::{self, File, OpenOptions},
-    io::Write,
+    fs::{self, File, OpenOptions, TryLockError},
+    io::{BufRead, BufReader, Read, Seek, SeekFrom, Write},
     path::{Path, PathBuf},
+    thread,
+    time::{Duration, Instant},
 };
 
 use serde_json::{Value, json};
 
 use crate::witness::record::{WitnessRecord, canonical_json};
 
-pub fn append(record: &WitnessRecord) -> std::io::Result<()> {
+/// How long `append` waits for the ledger lock before appending without it.
+const LOCK_WAIT: Duration = Duration::from_secs(5);
+const LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Whether `append` held the ledger lock while linking and writing.
+#[derive(Debug, PartialEq, Eq)]
+pub enum AppendLock {
+    Held,
+    /// The record was appended without the lock; the reason is for a warning.
+    Skipped(String),
+}
+
+/// Links `record` to the current ledger tail (`prev`), computes its id, and
+/// appends it. The tail read and the write happen under an exclusive advisory
+/// lock on the ledger file, so concurrent runs extend one linear chain.
+pub fn append(record: &mut WitnessRecord) -> std::io::Result<AppendLock> {
     ensure_ledger_migrated()?;
     prepare_canonical_tree_from_env(|key| env::var_os(key))?;
 
@@ -20,10 +37,57 @@ pub fn append(record: &WitnessRecord) -> std::io::Result<()> {
         harden_directory(parent)?;
     }
 
-    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
-    let encoded = canonical_json(record);
-    writeln!(file, "{encoded}")?;
-    Ok(())
+    let mut file = OpenOptions::new()
+        .create(true)
+        .read(true)
+        .append(true)
+        .open(path)?;
+    let lock = lock_with_timeout(&file, LOCK_WAIT);
+
+    file.seek(SeekFrom::Start(0))?;
+    record.prev = last_id(&mut file);
+    record.compute_id();
+    let encoded = format!("{}\n", canonical_json(record));
+    file.write_all(encoded.as_bytes())?;
+    file.flush()?;
+    if lock == AppendLock::Held {
+        file.unlock()?;
+    }
+    Ok(lock)
+}
+
+fn lock_with_timeout(file: &File, wait: Duration) -> AppendLock {
+    let deadline = Instant::now() + wait;
+    loop {
+        match file.try_lock() {
+            Ok(()) => return AppendLock::Held,
+            Err(TryLockError::WouldBlock) if Instant::now() < deadline => {
+                thread::sleep(LOCK_RETRY_INTERVAL);
+            }
+            Err(TryLockError::WouldBlock) => {
+                return AppendLock::Skipped(format!(
+                    "ledger still locked after {}s",
+                    wait.as_secs()
+                ));
+            }
+            Err(TryLockError::Error(error)) => return AppendLock::Skipped(error.to_string()),
+        }
+    }
+}
+
+/// Returns the `id` of the last non-empty line, if it parses.
+fn last_id<R: Read>(reader: R) -> Option<String> {
+    let mut last_non_empty = None;
+    for line in BufReader::new(reader).lines().map_while(Result::ok) {
+        let trimmed = line.trim();
+        if !trimmed.is_empty() {
+            last_non_empty = Some(trimmed.to_owned());
+        }
+    }
+
+    let last = last_non_empty?;
+    let value: serde_json::Value = serde_json::from_str(&last).ok()?;
+    value.get("id")?.as_str().map(ToOwned::to_owned)
 }
 
 pub fn resolve_ledger_path() -> PathBuf {
@@ -224,34 +288,43 @@ fn harden_directory(_path: &Path) -> std::io::Result<()> {
     Ok(())
 }
 
-pub fn read_prev() -> Option<String> {
-    let _ = ensure_ledger_migrated();
-
-    let path = resolve_ledger_path();
-    let file = File::open(path).ok()?;
-    let reader = std::io::BufReader::new(file);
-
-    let mut last_non_empty = None;
-    for line in std::io::BufRead::lines(reader).map_while(Result::ok) {
-        let trimmed = line.trim();
-        if !trimmed.is_empty() {
-            last_non_empty = Some(trimmed.to_owned());
-        }
-    }
-
-    let last = last_non_empty?;
-    let value: serde_json::Value = serde_json::from_str(&last).ok()?;
-    value.get("id")?.as_str().map(ToOwned::to_owned)
-}
-
 #[cfg(test)]
 mod tests {
-    use super::{ensure_ledger_migrated_from_env, resolve_ledger_path_from_env};
+    use super::{
+        AppendLock, ensure_ledger_migrated_from_env, lock_with_timeout,
+        resolve_ledger_path_from_env,
+    };
     use std::ffi::OsString;
-    use std::fs;
+    use std::fs::{self, OpenOptions};
     use std::path::PathBuf;
+    use std::time::Duration;
     use tempfile::TempDir;
 
+    #[test]
+    fn lock_wait_is_bounded_when_another_writer_holds_the_ledger() {
+        let temp = TempDir::new().expect("temp dir should be created");
+        let path = temp.path().join("witness.jsonl");
+        let open = || {
+            OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&path)
+                .expect("ledger should open")
+        };
+        let holder = open();
+        holder.lock().expect("first lock should succeed");
+
+        let waiter = open();
+        let outcome = lock_with_timeout(&waiter, Duration::from_millis(50));
+        assert!(matches!(outcome, AppendLock::Skipped(reason) if reason.contains("still locked")));
+
+        holder.unlock().expect("unlock should succeed");
+        assert_eq!(
+            lock_with_timeout(&waiter, Duration::from_millis(50)),
+            AppendLock::Held
+        );
+    }
+
     #[test]
     fn empty_epistemic_witness_falls_back_to_cmdrvl_home() {
         let path = resolve_ledger_path_from_env(|key| match key {
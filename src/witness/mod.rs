@@ -1,3 +1,4 @@
+pub mod chain;
 pub mod ledger;
 pub mod query;
 pub mod record;
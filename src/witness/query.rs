@@ -10,14 +10,6 @@ use crate::{
 const NO_MATCH_EXIT: u8 = 1;
 
 pub fn dispatch(action: &WitnessAction) -> u8 {
-    let entries = match read_entries() {
-        Ok(entries) => entries,
-        Err(error) => {
-            eprintln!("vacuum: witness read failed: {error}");
-            return crate::cli::exit::REFUSAL;
-        }
-    };
-
     match action {
         WitnessAction::Query {
             tool,
@@ -27,19 +19,23 @@ pub fn dispatch(action: &WitnessAction) -> u8 {
             input_hash,
             limit,
             json,
-        } => run_query(
-            &entries,
-            QueryFilter {
-                tool: tool.clone(),
-                since: since.clone(),
-                until: until.clone(),
-                outcome: outcome.clone(),
-                input_hash: input_hash.clone(),
-            },
-            *limit,
-            *json,
-        ),
-        WitnessAction::Last { json } => run_last(&entries, *json),
+        } => with_entries(|entries| {
+            run_query(
+                entries,
+                QueryFilter {
+                    tool: tool.clone(),
+                    since: since.clone(),
+                    until: until.clone(),
+                    outcome: outcome.clone(),
+                    input_hash: input_hash.clone(),
+                },
+                *limit,
+                *json,
+            )
+        }),
+        WitnessAction::Last { json } => with_entries(|entries| run_last(entries, *json)),
+        // Verify reads raw lines itself so it can report the ones parsing drops.
+        WitnessAction::Verify { json } => crate::witness::chain::run_verify(*json),
         WitnessAction::Count {
             tool,
             since,
@@ -47,17 +43,29 @@ pub fn dispatch(action: &WitnessAction) -> u8 {
             outcome,
             input_hash,
             json,
-        } => run_count(
-            &entries,
-            QueryFilter {
-                tool: tool.clone(),
-                since: since.clone(),
-                until: until.clone(),
-                outcome: outcome.clone(),
-                input_hash: input_hash.clone(),
-            },
-            *json,
-        ),
+        } => with_entries(|entries| {
+            run_count(
+                entries,
+                QueryFilter {
+                    tool: tool.clone(),
+                    since: since.clone(),
+                    until: until.clone(),
+                    outcome: outcome.clone(),
+                    input_hash: input_hash.clone(),
+                },
+                *json,
+            )
+        }),
+    }
+}
+
+fn with_entries(run: impl FnOnce(&[LedgerEntry]) -> u8) -> u8 {
+    match read_entries() {
+        Ok(entries) => run(&entries),
+        Err(error) => {
+            eprintln!("vacuum: witness read failed: {error}");
+            crate::cli::exit::REFUSAL
+        }
     }
 }
 
@@ -134,6 +142,22 @@ fn run_count(entries: &[LedgerEntry], filter: QueryFilter, json_mode: bool) -> u
     }
 }
 
+/// The latest vacuum run whose `output_hash` is `output_hash`, so an artifact
+/// built from a manifest can be traced to the scan that wrote it. An
+/// unreadable ledger is treated as having no match.
+pub(crate) fn find_run_by_output_hash(output_hash: &str) -> Option<Value> {
+    read_entries()
+        .ok()?
+        .iter()
+        .rev()
+        .map(|entry| &entry.value)
+        .find(|value| {
+            value.get("tool").and_then(Value::as_str) == Some("vacuum")
+                && value.get("output_hash").and_then(Value::as_str) == Some(output_hash)
+        })
+        .cloned()
+}
+
 fn read_entries() -> Result<Vec<LedgerEntry>, std::io::Error> {
     ensure_ledger_migrated()?;
 
@@ -31,12 +31,9 @@ pub struct WitnessRecord {
 }
 
 impl WitnessRecord {
-    #[allow(clippy::too_many_arguments)]
     pub fn from_run(
         roots: &[PathBuf],
-        include: &[String],
-        exclude: &[String],
-        no_follow: bool,
+        params: serde_json::Value,
         outcome: impl Into<String>,
         exit_code: u8,
         output_hash: String,
@@ -59,15 +56,7 @@ impl WitnessRecord {
                 .map(|value| format!("blake3:{value}"))
                 .unwrap_or_default(),
             inputs,
-            params: serde_json::json!({
-                "roots": roots
-                    .iter()
-                    .map(|root| root.to_string_lossy().into_owned())
-                    .collect::<Vec<_>>(),
-                "include": include,
-                "exclude": exclude,
-                "no_follow": no_follow,
-            }),
+            params,
             outcome: outcome.into(),
             exit_code,
             output_hash,
@@ -170,3 +170,780 @@ fn missing_root_refuses_with_exit_two() {
     assert_eq!(refusal["outcome"], "REFUSAL");
     assert_eq!(refusal["refusal"]["code"], "E_ROOT_NOT_FOUND");
 }
+
+#[test]
+fn threaded_scan_is_byte_identical_to_serial_scan() {
+    let roots = [fixture("nested"), fixture("simple"), fixture("mixed")];
+
+    let serial = support::vacuum_command("core-threads-serial")
+        .args(&roots)
+        .arg("--no-witness")
+        .output()
+        .expect("vacuum binary should run");
+    let threaded = support::vacuum_command("core-threads-parallel")
+        .args(&roots)
+        .args(["--threads", "4", "--no-witness"])
+        .output()
+        .expect("vacuum binary should run");
+
+    assert!(serial.status.success(), "serial scan should exit 0");
+    assert!(threaded.status.success(), "threaded scan should exit 0");
+    assert!(!serial.stdout.is_empty());
+    assert_eq!(threaded.stdout, serial.stdout);
+}
+
+#[test]
+fn zero_threads_is_rejected_as_usage_error() {
+    let output = support::vacuum_command("core-threads-zero")
+        .arg(fixture("simple"))
+        .args(["--threads", "0", "--no-witness"])
+        .output()
+        .expect("vacuum binary should run");
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn spilled_sort_runs_produce_byte_identical_manifest() {
+    let roots = [fixture("large_file_counts"), fixture("nested")];
+    let spill_parent = tempfile::tempdir().expect("tempdir should be created");
+
+    let in_memory = support::vacuum_command("core-sort-memory")
+        .args(&roots)
+        .arg("--no-witness")
+        .output()
+        .expect("vacuum binary should run");
+    let spilled = support::vacuum_command("core-sort-spilled")
+        .args(&roots)
+        .args(["--sort-buffer", "2", "--threads", "3", "--no-witness"])
+        .arg("--spill-dir")
+        .arg(spill_parent.path())
+        .output()
+        .expect("vacuum binary should run");
+
+    assert!(in_memory.status.success(), "in-memory scan should exit 0");
+    assert!(spilled.status.success(), "spilled scan should exit 0");
+    assert_eq!(spilled.stdout, in_memory.stdout);
+    assert_eq!(
+        std::fs::read_dir(spill_parent.path())
+            .expect("spill parent should be readable")
+            .count(),
+        0,
+        "sorted runs should be removed after emission"
+    );
+}
+
+#[test]
+fn unusable_spill_dir_refuses_with_io_code() {
+    let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+    let not_a_dir = temp_dir.path().join("spill-file");
+    std::fs::write(&not_a_dir, "x").expect("spill file should be written");
+
+    let output = support::vacuum_command("core-sort-refusal")
+        .arg(fixture("large_file_counts"))
+        .args(["--sort-buffer", "2", "--no-witness"])
+        .arg("--spill-dir")
+        .arg(&not_a_dir)
+        .output()
+        .expect("vacuum binary should run");
+
+    assert_eq!(output.status.code(), Some(2));
+    let refusal: Value =
+        serde_json::from_slice(&output.stdout).expect("refusal stdout should be valid json");
+    assert_eq!(refusal["refusal"]["code"], "E_IO");
+    assert_eq!(
+        refusal["refusal"]["detail"]["spill_dir"],
+        not_a_dir.to_string_lossy().as_ref()
+    );
+}
+
+#[test]
+fn max_depth_guardrail_refuses_with_too_deep_code() {
+    let output = support::vacuum_command("core-max-depth")
+        .arg(fixture("deeply_nested"))
+        .args(["--max-depth", "4", "--no-witness"])
+        .output()
+        .expect("vacuum binary should run");
+
+    assert_eq!(output.status.code(), Some(2));
+    let refusal: Value =
+        serde_json::from_slice(&output.stdout).expect("refusal stdout should be valid json");
+    assert_eq!(refusal["outcome"], "REFUSAL");
+    assert_eq!(refusal["refusal"]["code"], "E_TOO_DEEP");
+    assert_eq!(refusal["refusal"]["detail"]["max_depth"], 4);
+    assert!(
+        refusal["refusal"]["next_command"]
+            .as_str()
+            .is_some_and(|command| command.starts_with("ls -la "))
+    );
+}
+
+#[test]
+fn max_files_guardrail_refuses_with_count_reached() {
+    let output = support::vacuum_command("core-max-files")
+        .arg(fixture("large_file_counts"))
+        .args(["--max-files", "5", "--threads", "3", "--no-witness"])
+        .output()
+        .expect("vacuum binary should run");
+
+    assert_eq!(output.status.code(), Some(2));
+    let refusal: Value =
+        serde_json::from_slice(&output.stdout).expect("refusal stdout should be valid json");
+    assert_eq!(refusal["refusal"]["code"], "E_TOO_MANY_FILES");
+    assert_eq!(refusal["refusal"]["detail"]["max_files"], 5);
+    assert_eq!(refusal["refusal"]["detail"]["files_seen"], 6);
+}
+
+#[test]
+fn sniff_flag_detects_misnamed_files_without_changing_mime_guess() {
+    let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+    std::fs::write(temp_dir.path().join("vendor.dat"), [0x1f, 0x8b, 0x08, 0x00])
+        .expect("gzip fixture should be written");
+    std::fs::write(
+        temp_dir.path().join("report.xls"),
+        "<html><body><table></table></body></html>",
+    )
+    .expect("html fixture should be written");
+    std::fs::write(temp_dir.path().join("plain.csv"), "id,name\n1,a\n")
+        .expect("csv fixture should be written");
+
+    let plain = support::vacuum_command("core-no-sniff")
+        .arg(temp_dir.path())
+        .arg("--no-witness")
+        .output()
+        .expect("vacuum binary should run");
+    assert!(plain.status.success());
+    assert!(
+        parse_json_lines(&plain.stdout)
+            .iter()
+            .all(|row| row.get("mime_sniffed").is_none())
+    );
+
+    let sniffed = support::vacuum_command("core-sniff")
+        .arg(temp_dir.path())
+        .args(["--sniff", "--no-witness"])
+        .output()
+        .expect("vacuum binary should run");
+    assert!(sniffed.status.success());
+    let rows = parse_json_lines(&sniffed.stdout);
+    let by_path = |relative_path: &str| {
+        rows.iter()
+            .find(|row| row["relative_path"] == relative_path)
+            .expect("row should exist")
+            .clone()
+    };
+
+    let gzip = by_path("vendor.dat");
+    assert!(gzip["mime_guess"].is_null());
+    assert_eq!(gzip["mime_sniffed"], "application/gzip");
+
+    let html = by_path("report.xls");
+    assert_eq!(html["mime_guess"], "application/vnd.ms-excel");
+    assert_eq!(html["mime_sniffed"], "text/html");
+
+    assert!(by_path("plain.csv").get("mime_sniffed").is_none());
+}
+
+#[cfg(unix)]
+#[test]
+fn posix_flag_records_mode_ownership_and_hard_links() {
+    use std::os::unix::fs::{MetadataExt, PermissionsExt};
+
+    let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+    let shared = temp_dir.path().join("shared.csv");
+    std::fs::write(&shared, "id\n").expect("fixture should be written");
+    std::fs::set_permissions(&shared, std::fs::Permissions::from_mode(0o666))
+        .expect("permissions should be set");
+    std::fs::hard_link(&shared, temp_dir.path().join("copy.csv"))
+        .expect("hard link should be created");
+    let metadata = std::fs::metadata(&shared).expect("metadata should be readable");
+
+    let plain = support::vacuum_command("core-no-posix")
+        .arg(temp_dir.path())
+        .arg("--no-witness")
+        .output()
+        .expect("vacuum binary should run");
+    assert!(
+        parse_json_lines(&plain.stdout)
+            .iter()
+            .all(|row| row.get("mode").is_none() && row.get("inode").is_none())
+    );
+
+    let output = support::vacuum_command("core-posix")
+        .arg(temp_dir.path())
+        .args(["--posix", "--owner-names", "--no-witness"])
+        .output()
+        .expect("vacuum binary should run");
+    assert!(output.status.success());
+    let rows = parse_json_lines(&output.stdout);
+    assert_eq!(rows.len(), 2);
+    for row in &rows {
+        assert_eq!(row["mode"], "0666");
+        assert_eq!(row["uid"], metadata.uid());
+        assert_eq!(row["gid"], metadata.gid());
+        assert_eq!(row["inode"], metadata.ino());
+        assert_eq!(row["dev"], metadata.dev());
+        assert_eq!(row["nlink"], 2);
+        assert!(row.get("user").is_none_or(Value::is_string));
+    }
+}
+
+#[test]
+fn owner_names_requires_posix() {
+    let output = support::vacuum_command("core-owner-names")
+        .arg(fixture("simple"))
+        .args(["--owner-names", "--no-witness"])
+        .output()
+        .expect("vacuum binary should run");
+
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn times_flags_add_ctime_btime_and_atime() {
+    let plain = support::vacuum_command("core-no-times")
+        .arg(fixture("simple"))
+        .arg("--no-witness")
+        .output()
+        .expect("vacuum binary should run");
+    assert!(parse_json_lines(&plain.stdout).iter().all(|row| {
+        row.get("ctime").is_none() && row.get("btime").is_none() && row.get("atime").is_none()
+    }));
+
+    let output = support::vacuum_command("core-times")
+        .arg(fixture("simple"))
+        .args(["--times", "--no-witness"])
+        .output()
+        .expect("vacuum binary should run");
+    assert!(output.status.success());
+    for row in parse_json_lines(&output.stdout) {
+        let mtime = row["mtime"].as_str().expect("mtime should be a string");
+        let ctime = row["ctime"].as_str().expect("ctime should be a string");
+        assert_eq!(ctime.len(), mtime.len(), "same millisecond RFC 3339 shape");
+        assert!(ctime.ends_with('Z'));
+        let btime = row
+            .get("btime")
+            .expect("btime is always present with --times");
+        assert!(btime.is_null() || btime.as_str().is_some_and(|value| value.ends_with('Z')));
+        assert!(row.get("atime").is_none());
+    }
+
+    let output = support::vacuum_command("core-atime")
+        .arg(fixture("simple"))
+        .args(["--atime", "--no-witness"])
+        .output()
+        .expect("vacuum binary should run");
+    for row in parse_json_lines(&output.stdout) {
+        assert!(row["atime"].is_string());
+        assert!(row.get("ctime").is_none());
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn special_files_report_their_file_type() {
+    use std::os::unix::{fs::symlink, net::UnixListener};
+
+    let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+    let root = temp_dir.path();
+    std::fs::write(root.join("plain.csv"), "id\n").expect("file should be written");
+    let _listener = UnixListener::bind(root.join("agent.sock")).expect("socket should bind");
+    symlink("/dev/null", root.join("null")).expect("device symlink should be created");
+    symlink("plain.csv", root.join("link.csv")).expect("file symlink should be created");
+
+    let types = |extra_args: &[&str]| {
+        let output = support::vacuum_command("core-file-type")
+            .arg(root)
+            .arg("--no-witness")
+            .args(extra_args)
+            .output()
+            .expect("vacuum binary should run");
+        assert!(output.status.success());
+        parse_json_lines(&output.stdout)
+            .iter()
+            .map(|row| {
+                (
+                    row["relative_path"].as_str().unwrap_or_default().to_owned(),
+                    row["file_type"].as_str().unwrap_or_default().to_owned(),
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+    let pairs = |expected: &[(&str, &str)]| {
+        expected
+            .iter()
+            .map(|(path, kind)| (path.to_string(), kind.to_string()))
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        types(&[]),
+        pairs(&[
+            ("agent.sock", "socket"),
+            ("link.csv", "file"),
+            ("null", "char"),
+            ("plain.csv", "file"),
+        ])
+    );
+    assert_eq!(
+        types(&["--no-follow"]),
+        pairs(&[
+            ("agent.sock", "socket"),
+            ("link.csv", "symlink"),
+            ("null", "symlink"),
+            ("plain.csv", "file"),
+        ])
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn symlink_records_carry_target_and_escape_flag() {
+    use std::os::unix::fs::symlink;
+
+    let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+    let root = temp_dir.path().join("delivery");
+    std::fs::create_dir_all(&root).expect("root should be created");
+    std::fs::write(root.join("tape.csv"), "id\n").expect("file should be written");
+    std::fs::write(temp_dir.path().join("outside.csv"), "id\n").expect("file should be written");
+    symlink("tape.csv", root.join("alias.csv")).expect("link should be created");
+    symlink("../outside.csv", root.join("escape.csv")).expect("link should be created");
+
+    for extra_args in [&[][..], &["--no-follow"][..]] {
+        let output = support::vacuum_command("core-symlink-info")
+            .arg(&root)
+            .arg("--no-witness")
+            .args(extra_args)
+            .output()
+            .expect("vacuum binary should run");
+        assert!(output.status.success());
+        let rows = parse_json_lines(&output.stdout);
+        let by_path = |relative_path: &str| {
+            rows.iter()
+                .find(|row| row["relative_path"] == relative_path)
+                .expect("row should exist")
+                .clone()
+        };
+
+        let alias = by_path("alias.csv");
+        assert_eq!(alias["symlink"]["target"], "tape.csv");
+        assert_eq!(alias["symlink"]["escapes_root"], false);
+        assert!(
+            alias["symlink"]["resolved"]
+                .as_str()
+                .is_some_and(|resolved| resolved.ends_with("delivery/tape.csv"))
+        );
+
+        let escape = by_path("escape.csv");
+        assert_eq!(escape["symlink"]["target"], "../outside.csv");
+        assert_eq!(escape["symlink"]["escapes_root"], true);
+
+        assert!(by_path("tape.csv").get("symlink").is_none());
+    }
+
+    let output = support::vacuum_command("core-symlink-broken")
+        .arg(fixture("symlinks"))
+        .arg("--no-witness")
+        .output()
+        .expect("vacuum binary should run");
+    let rows = parse_json_lines(&output.stdout);
+    let broken = rows
+        .iter()
+        .find(|row| row["relative_path"] == "broken_link")
+        .expect("broken link should be represented");
+    assert_eq!(broken["_skipped"], true);
+    assert_eq!(broken["symlink"]["target"], "missing-target.txt");
+    assert!(broken["symlink"]["resolved"].is_null());
+    assert_eq!(broken["symlink"]["escapes_root"], false);
+}
+
+#[test]
+fn roots_from_file_and_stdin_match_positional_roots() {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+    let list = temp_dir.path().join("roots.txt");
+    std::fs::write(
+        &list,
+        format!(
+            "{}\n\n{}\n",
+            fixture("nested").display(),
+            fixture("simple").display()
+        ),
+    )
+    .expect("root list should be written");
+
+    let positional = support::vacuum_command("core-roots-positional")
+        .args([fixture("nested"), fixture("simple")])
+        .arg("--no-witness")
+        .output()
+        .expect("vacuum binary should run");
+    let from_file = support::vacuum_command("core-roots-from-file")
+        .arg("--roots-from")
+        .arg(&list)
+        .arg("--no-witness")
+        .output()
+        .expect("vacuum binary should run");
+
+    let mut child = support::vacuum_command("core-roots-from-stdin")
+        .args(["--roots-from", "-", "--no-witness"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("vacuum binary should spawn");
+    child
+        .stdin
+        .take()
+        .expect("stdin should be piped")
+        .write_all(
+            format!(
+                "{}\0{}\0",
+                fixture("simple").display(),
+                fixture("nested").display()
+            )
+            .as_bytes(),
+        )
+        .expect("root list should be written to stdin");
+    let from_stdin = child.wait_with_output().expect("vacuum binary should run");
+
+    assert!(positional.status.success(), "positional scan should exit 0");
+    assert!(
+        from_file.status.success(),
+        "--roots-from FILE should exit 0"
+    );
+    assert!(from_stdin.status.success(), "--roots-from - should exit 0");
+    assert!(!positional.stdout.is_empty());
+    assert_eq!(from_file.stdout, positional.stdout);
+    assert_eq!(from_stdin.stdout, positional.stdout);
+}
+
+#[test]
+fn roots_from_validates_every_listed_root() {
+    let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+    let list = temp_dir.path().join("roots.txt");
+    std::fs::write(
+        &list,
+        format!(
+            "{}\n{}\n",
+            fixture("simple").display(),
+            temp_dir.path().join("missing").display()
+        ),
+    )
+    .expect("root list should be written");
+
+    let output = support::vacuum_command("core-roots-from-missing")
+        .arg("--roots-from")
+        .arg(&list)
+        .arg("--no-witness")
+        .output()
+        .expect("vacuum binary should run");
+    assert_eq!(output.status.code(), Some(2));
+    let refusal = &parse_json_lines(&output.stdout)[0];
+    assert_eq!(refusal["refusal"]["code"], "E_ROOT_NOT_FOUND");
+
+    let unreadable = support::vacuum_command("core-roots-from-unreadable")
+        .arg("--roots-from")
+        .arg(temp_dir.path().join("no-such-list.txt"))
+        .arg("--no-witness")
+        .output()
+        .expect("vacuum binary should run");
+    assert_eq!(unreadable.status.code(), Some(2));
+    let refusal = &parse_json_lines(&unreadable.stdout)[0];
+    assert_eq!(refusal["refusal"]["code"], "E_IO");
+    assert!(refusal["refusal"]["detail"]["roots_from"].is_string());
+}
+
+#[test]
+fn files_from_records_only_listed_paths_under_one_root() {
+    let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+    let list = temp_dir.path().join("files.txt");
+    std::fs::write(&list, "region/north.tsv\nmissing.csv\nroot.txt\n")
+        .expect("file list should be written");
+
+    let output = support::vacuum_command("core-files-from")
+        .arg(fixture("nested"))
+        .arg("--files-from")
+        .arg(&list)
+        .arg("--no-witness")
+        .output()
+        .expect("vacuum binary should run");
+
+    assert!(output.status.success(), "--files-from should exit 0");
+    let rows = parse_json_lines(&output.stdout);
+    let relative_paths = rows
+        .iter()
+        .map(|row| row["relative_path"].as_str().unwrap_or_default())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        relative_paths,
+        vec!["missing.csv", "region/north.tsv", "root.txt"]
+    );
+    assert_eq!(rows[0]["_skipped"], true);
+    assert!(rows[1].get("_skipped").is_none());
+
+    let walked = support::vacuum_command("core-files-from-walked")
+        .arg(fixture("nested"))
+        .arg("--no-witness")
+        .output()
+        .expect("vacuum binary should run");
+    let walked_root = parse_json_lines(&walked.stdout)
+        .into_iter()
+        .find(|row| row["relative_path"] == "root.txt")
+        .expect("walk should record root.txt");
+    assert_eq!(rows[2], walked_root);
+
+    let two_roots = support::vacuum_command("core-files-from-two-roots")
+        .args([fixture("nested"), fixture("simple")])
+        .arg("--files-from")
+        .arg(&list)
+        .arg("--no-witness")
+        .output()
+        .expect("vacuum binary should run");
+    assert_eq!(two_roots.status.code(), Some(2));
+}
+
+#[test]
+fn size_and_mtime_predicates_compose_with_globs() {
+    let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+    let root = temp_dir.path();
+    std::fs::write(root.join("empty.csv"), "").expect("file should be written");
+    std::fs::write(root.join("small.csv"), "a,b\n").expect("file should be written");
+    std::fs::write(root.join("large.csv"), "x".repeat(2048)).expect("file should be written");
+    std::fs::write(root.join("small.txt"), "a,b\n").expect("file should be written");
+
+    let relative_paths = |args: &[&str]| {
+        let output = support::vacuum_command("core-predicates")
+            .arg(root)
+            .args(args)
+            .arg("--no-witness")
+            .output()
+            .expect("vacuum binary should run");
+        assert!(output.status.success(), "scan should exit 0");
+        parse_json_lines(&output.stdout)
+            .iter()
+            .map(|row| {
+                row["relative_path"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string()
+            })
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        relative_paths(&[
+            "--include",
+            "*.csv",
+            "--min-size",
+            "1",
+            "--max-size",
+            "1KiB"
+        ]),
+        vec!["small.csv"]
+    );
+    assert_eq!(
+        relative_paths(&["--newer-than", "1h", "--exclude", "*.txt"]),
+        vec!["empty.csv", "large.csv", "small.csv"]
+    );
+    assert!(relative_paths(&["--older-than", "1h"]).is_empty());
+
+    let invalid = support::vacuum_command("core-predicates-invalid")
+        .arg(root)
+        .args(["--max-size", "10 parsecs", "--no-witness"])
+        .output()
+        .expect("vacuum binary should run");
+    assert_eq!(invalid.status.code(), Some(2));
+
+    let inverted = support::vacuum_command("core-predicates-inverted")
+        .arg(root)
+        .args(["--min-size", "1KiB", "--max-size", "1", "--no-witness"])
+        .output()
+        .expect("vacuum binary should run");
+    assert_eq!(inverted.status.code(), Some(2));
+    assert!(inverted.stdout.is_empty(), "usage errors write no records");
+
+    let inverted_times = support::vacuum_command("core-predicates-inverted-times")
+        .arg(root)
+        .args(["--newer-than", "1h", "--older-than", "2h", "--no-witness"])
+        .output()
+        .expect("vacuum binary should run");
+    assert_eq!(inverted_times.status.code(), Some(2));
+    assert!(
+        inverted_times.stdout.is_empty(),
+        "usage errors write no records"
+    );
+}
+
+#[test]
+fn regex_and_case_insensitive_patterns_select_records() {
+    let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+    let root = temp_dir.path();
+    for name in ["upper.CSV", "lower.csv", "notes.txt", "q1-2026.txt"] {
+        std::fs::write(root.join(name), "x").expect("file should be written");
+    }
+
+    let output = support::vacuum_command("core-pattern-modes")
+        .arg(root)
+        .args(["--include", "*.csv", "--glob-case-insensitive"])
+        .args(["--include-regex", r"^q\d-\d{4}\.txt$"])
+        .args(["--exclude-regex", "^lower"])
+        .arg("--no-witness")
+        .output()
+        .expect("vacuum binary should run");
+
+    assert!(output.status.success(), "scan should exit 0");
+    let relative_paths = parse_json_lines(&output.stdout)
+        .iter()
+        .map(|row| {
+            row["relative_path"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(relative_paths, vec!["q1-2026.txt", "upper.CSV"]);
+}
+
+#[test]
+fn invalid_pattern_refuses_with_bad_pattern_code() {
+    let output = support::vacuum_command("core-bad-pattern")
+        .arg(fixture("simple"))
+        .args([
+            "--include",
+            "*.csv",
+            "--exclude-regex",
+            "data(",
+            "--no-witness",
+        ])
+        .output()
+        .expect("vacuum binary should run");
+
+    assert_eq!(output.status.code(), Some(2));
+    let refusal = &parse_json_lines(&output.stdout)[0];
+    assert_eq!(refusal["refusal"]["code"], "E_BAD_PATTERN");
+    assert_eq!(refusal["refusal"]["detail"]["flag"], "--exclude-regex");
+    assert_eq!(refusal["refusal"]["detail"]["pattern"], "data(");
+}
+
+#[test]
+fn csv_and_tsv_formats_emit_a_header_and_one_row_per_record() {
+    let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+    let root = temp_dir.path();
+    std::fs::write(root.join("a,b.csv"), "x").expect("file should be written");
+    std::fs::write(root.join("plain.txt"), "yy").expect("file should be written");
+
+    let csv = support::vacuum_command("core-format-csv")
+        .arg(root)
+        .args(["--format", "csv", "--no-witness"])
+        .output()
+        .expect("vacuum binary should run");
+    assert!(csv.status.success(), "scan should exit 0");
+    let csv = String::from_utf8(csv.stdout).expect("stdout should be utf-8");
+    let rows = csv.split_terminator("\r\n").collect::<Vec<_>>();
+    assert_eq!(rows.len(), 3);
+    assert!(rows[0].starts_with("path,relative_path,root,size,mtime,"));
+    assert!(rows[0].ends_with(",skipped,warnings,warnings_json"));
+    assert!(rows[1].contains(",\"a,b.csv\","));
+    assert!(rows[2].contains(",plain.txt,"));
+
+    let tsv = support::vacuum_command("core-format-tsv")
+        .arg(root)
+        .args(["--format", "tsv", "--no-witness"])
+        .output()
+        .expect("vacuum binary should run");
+    assert!(tsv.status.success(), "scan should exit 0");
+    let tsv = String::from_utf8(tsv.stdout).expect("stdout should be utf-8");
+    let rows = tsv.lines().collect::<Vec<_>>();
+    assert_eq!(rows.len(), 3);
+    let columns = rows[0].split('\t').collect::<Vec<_>>();
+    let plain = rows[2].split('\t').collect::<Vec<_>>();
+    assert_eq!(columns.len(), plain.len());
+    assert_eq!(plain[1], "plain.txt");
+    assert_eq!(plain[3], "2");
+}
+
+#[test]
+fn human_mode_renders_table_or_tree_with_totals() {
+    let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+    let root = temp_dir.path();
+    std::fs::create_dir_all(root.join("q1")).expect("dir should be created");
+    std::fs::write(root.join("q1/a.csv"), "x".repeat(2048)).expect("file should be written");
+    std::fs::write(root.join("top.txt"), "hi").expect("file should be written");
+
+    let table = support::vacuum_command("core-human-table")
+        .arg("--human")
+        .arg(root)
+        .arg("--no-witness")
+        .output()
+        .expect("vacuum binary should run");
+    assert!(table.status.success(), "scan should exit 0");
+    let table = String::from_utf8(table.stdout).expect("stdout should be utf-8");
+    let lines = table.lines().collect::<Vec<_>>();
+    assert_eq!(lines[0], "      SIZE  MODIFIED    TYPE     PATH");
+    assert!(lines[1].starts_with("   2.0 KiB  "));
+    assert!(lines[1].ends_with("  file     q1/a.csv"));
+    assert!(
+        !table.contains('\x1b'),
+        "no color when stdout is not a terminal"
+    );
+    assert_eq!(
+        lines.last().map(|line| line.split(';').next()),
+        Some(Some("2 files, 2.0 KiB (2050 bytes)"))
+    );
+
+    let tree = support::vacuum_command("core-human-tree")
+        .arg(root)
+        .args(["--human=tree", "--no-witness"])
+        .output()
+        .expect("vacuum binary should run");
+    assert!(tree.status.success(), "scan should exit 0");
+    let tree = String::from_utf8(tree.stdout).expect("stdout should be utf-8");
+    assert!(tree.contains("├── q1/\n│   └── a.csv  2.0 KiB  "));
+    assert!(tree.contains("└── top.txt  2 B  "));
+
+    let conflict = support::vacuum_command("core-human-conflict")
+        .arg(root)
+        .args(["--human", "--format", "csv", "--no-witness"])
+        .output()
+        .expect("vacuum binary should run");
+    assert_eq!(conflict.status.code(), Some(2));
+}
+
+/// A followed link to `/proc` stands in for a mount point, since tests cannot
+/// mount anything.
+#[cfg(target_os = "linux")]
+#[test]
+fn one_file_system_marks_mount_points_as_skipped() {
+    use std::os::unix::fs::{MetadataExt, symlink};
+
+    let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+    let root = temp_dir.path();
+    let device = |path: &std::path::Path| std::fs::metadata(path).ok().map(|meta| meta.dev());
+    let proc_device = device(std::path::Path::new("/proc"));
+    assert!(proc_device.is_some(), "/proc should be mounted");
+    assert_ne!(
+        proc_device,
+        device(root),
+        "/proc should not share the temp dir's device"
+    );
+    std::fs::write(root.join("local.txt"), "x").expect("file should be written");
+    symlink("/proc", root.join("proc")).expect("link should be created");
+
+    let output = support::vacuum_command("core-one-file-system")
+        .arg(root)
+        .args(["--one-file-system", "--no-witness"])
+        .output()
+        .expect("vacuum binary should run");
+    assert!(output.status.success(), "scan should exit 0");
+    let rows = parse_json_lines(&output.stdout);
+
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0]["relative_path"], "local.txt");
+    assert_eq!(rows[1]["relative_path"], "proc");
+    assert_eq!(rows[1]["_skipped"], true);
+    assert_eq!(rows[1]["file_type"], "dir");
+    assert_eq!(rows[1]["_warnings"][0]["code"], "W_SYMLINK_ESCAPES_ROOT");
+    assert_eq!(rows[1]["_warnings"][1]["code"], "W_OTHER_FILESYSTEM");
+}
@@ -31,7 +31,9 @@ fn filter_matches(path: &str, include: &[&str], exclude: &[&str]) -> bool {
         .iter()
         .map(|value| value.to_string())
         .collect::<Vec<_>>();
-    !apply_filters(vec![record(path)], &include, &exclude).is_empty()
+    !apply_filters(vec![record(path)], &include, &exclude)
+        .expect("patterns should compile")
+        .is_empty()
 }
 
 fn run_scan(paths: &[PathBuf]) -> Vec<Value> {
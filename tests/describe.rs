@@ -20,6 +20,8 @@ fn describe_prints_operator_manifest_without_roots() {
     assert_eq!(manifest["schema_version"], "operator.v0");
     assert_eq!(manifest["name"], "vacuum");
     assert_eq!(manifest["exit_codes"]["0"]["meaning"], "SCAN_COMPLETE");
+    assert_eq!(manifest["exit_codes"]["1"]["meaning"], "DIFFERENCE");
+    assert!(manifest["exit_codes"]["1"]["commands"]["verify"].is_string());
     assert_eq!(manifest["exit_codes"]["2"]["meaning"], "REFUSAL");
     assert_eq!(manifest["pipeline"]["upstream"], serde_json::json!([]));
     assert_eq!(
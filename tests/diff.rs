@@ -0,0 +1,140 @@
+use std::fs;
+
+use serde_json::Value;
+
+mod support;
+
+fn parse_json_lines(stdout: &[u8]) -> Vec<Value> {
+    String::from_utf8(stdout.to_vec())
+        .expect("stdout should be utf-8")
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("line should be valid json"))
+        .collect()
+}
+
+#[test]
+fn identical_manifests_exit_zero_with_no_events() {
+    let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+    let root = temp_dir.path().join("delivery");
+    fs::create_dir_all(&root).expect("root should be created");
+    fs::write(root.join("tape.csv"), "id\n1\n").expect("file should be written");
+    let manifest = temp_dir.path().join("old.jsonl");
+    support::scan_to(&root, &manifest, "diff-identical", &[]);
+
+    let output = support::vacuum_command("diff-identical-run")
+        .arg("diff")
+        .arg(&manifest)
+        .arg(&manifest)
+        .output()
+        .expect("vacuum binary should run");
+
+    assert_eq!(output.status.code(), Some(0));
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn changed_delivery_reports_events_and_exits_one() {
+    let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+    let root = temp_dir.path().join("delivery");
+    fs::create_dir_all(&root).expect("root should be created");
+    fs::write(root.join("kept.csv"), "id\n1\n").expect("file should be written");
+    fs::write(root.join("gone.csv"), "id\n").expect("file should be written");
+    let old_manifest = temp_dir.path().join("old.jsonl");
+    support::scan_to(&root, &old_manifest, "diff-old", &[]);
+
+    fs::remove_file(root.join("gone.csv")).expect("file should be removed");
+    fs::write(root.join("kept.csv"), "id\n1\n2\n").expect("file should be rewritten");
+    fs::write(root.join("new.csv"), "id\n").expect("file should be written");
+    let new_manifest = temp_dir.path().join("new.jsonl");
+    support::scan_to(&root, &new_manifest, "diff-new", &[]);
+
+    let output = support::vacuum_command("diff-changed")
+        .arg("diff")
+        .arg(&old_manifest)
+        .arg(&new_manifest)
+        .output()
+        .expect("vacuum binary should run");
+
+    assert_eq!(output.status.code(), Some(1));
+    let events = parse_json_lines(&output.stdout);
+    let summary = events
+        .iter()
+        .filter(|event| event["event"] != "mtime_changed")
+        .map(|event| {
+            (
+                event["relative_path"].as_str().unwrap_or_default(),
+                event["event"].as_str().unwrap_or_default(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        summary,
+        vec![
+            ("gone.csv", "removed"),
+            ("kept.csv", "size_changed"),
+            ("new.csv", "added"),
+        ]
+    );
+    let size_changed = &events[1];
+    assert_eq!(size_changed["old"], 5);
+    assert_eq!(size_changed["new"], 7);
+    assert_eq!(events[0]["record"]["relative_path"], "gone.csv");
+}
+
+#[test]
+fn ignore_root_joins_deliveries_scanned_from_different_roots() {
+    let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+    let january = temp_dir.path().join("2026-01");
+    let february = temp_dir.path().join("2026-02");
+    for root in [&january, &february] {
+        fs::create_dir_all(root).expect("root should be created");
+        fs::write(root.join("tape.csv"), "id\n1\n").expect("file should be written");
+    }
+    let old_manifest = temp_dir.path().join("old.jsonl");
+    let new_manifest = temp_dir.path().join("new.jsonl");
+    support::scan_to(&january, &old_manifest, "diff-jan", &[]);
+    support::scan_to(&february, &new_manifest, "diff-feb", &[]);
+
+    let by_identity = support::vacuum_command("diff-identity")
+        .arg("diff")
+        .arg(&old_manifest)
+        .arg(&new_manifest)
+        .output()
+        .expect("vacuum binary should run");
+    assert_eq!(by_identity.status.code(), Some(1));
+    let events = parse_json_lines(&by_identity.stdout);
+    assert_eq!(events.len(), 2);
+
+    let by_path = support::vacuum_command("diff-ignore-root")
+        .arg("diff")
+        .arg(&old_manifest)
+        .arg(&new_manifest)
+        .arg("--ignore-root")
+        .output()
+        .expect("vacuum binary should run");
+    let events = parse_json_lines(&by_path.stdout);
+    assert!(
+        events.iter().all(|event| event["event"] == "mtime_changed"),
+        "only mtimes may differ between the copies: {events:?}"
+    );
+}
+
+#[test]
+fn non_manifest_input_refuses_with_bad_manifest_code() {
+    let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+    let manifest = temp_dir.path().join("not-a-manifest.jsonl");
+    fs::write(&manifest, "{\"hello\":\"world\"}\n").expect("file should be written");
+
+    let output = support::vacuum_command("diff-bad")
+        .arg("diff")
+        .arg(&manifest)
+        .arg(&manifest)
+        .output()
+        .expect("vacuum binary should run");
+
+    assert_eq!(output.status.code(), Some(2));
+    let refusal: Value =
+        serde_json::from_slice(&output.stdout).expect("refusal should be valid json");
+    assert_eq!(refusal["refusal"]["code"], "E_BAD_MANIFEST");
+    assert_eq!(refusal["refusal"]["detail"]["line"], 1);
+}
@@ -0,0 +1,133 @@
+use std::fs;
+
+#[cfg(feature = "sqlite")]
+use rusqlite::Connection;
+
+mod support;
+
+#[cfg(feature = "sqlite")]
+fn metadata(connection: &Connection, key: &str) -> Option<String> {
+    connection
+        .query_row("SELECT value FROM metadata WHERE key = ?1", [key], |row| {
+            row.get(0)
+        })
+        .expect("metadata row should exist")
+}
+
+#[cfg(feature = "sqlite")]
+#[test]
+fn sqlite_export_traces_back_to_the_witnessed_scan() {
+    let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+    let witness_path = temp_dir.path().join("witness.jsonl");
+    let root = temp_dir.path().join("delivery");
+    fs::create_dir_all(root.join("q1")).expect("root should be created");
+    fs::write(root.join("q1/a.csv"), "id\n1\n").expect("file should be written");
+    fs::write(root.join("b.txt"), "hello").expect("file should be written");
+
+    let scan = support::vacuum_command("export-scan")
+        .arg(&root)
+        .env("EPISTEMIC_WITNESS", &witness_path)
+        .output()
+        .expect("vacuum binary should run");
+    assert!(scan.status.success(), "scan should exit 0");
+    let manifest = temp_dir.path().join("manifest.jsonl");
+    fs::write(&manifest, &scan.stdout).expect("manifest should be written");
+
+    let out = temp_dir.path().join("manifest.db");
+    let output = support::vacuum_command("export-run")
+        .arg("export")
+        .arg(&manifest)
+        .arg("--sqlite")
+        .arg(&out)
+        .env("EPISTEMIC_WITNESS", &witness_path)
+        .output()
+        .expect("vacuum binary should run");
+    assert_eq!(output.status.code(), Some(0));
+
+    let witness = fs::read_to_string(&witness_path).expect("witness should be readable");
+    let run = serde_json::from_str::<serde_json::Value>(
+        witness.lines().next().expect("one witness record"),
+    )
+    .expect("witness line should parse");
+    assert_eq!(
+        witness.lines().count(),
+        1,
+        "export appends no witness record"
+    );
+
+    let connection = Connection::open(&out).expect("database should open");
+    assert_eq!(
+        metadata(&connection, "schema_version").as_deref(),
+        Some("vacuum.sqlite.v0")
+    );
+    assert_eq!(
+        metadata(&connection, "witness_id"),
+        run["id"].as_str().map(str::to_string)
+    );
+    assert_eq!(
+        metadata(&connection, "output_hash"),
+        run["output_hash"].as_str().map(str::to_string)
+    );
+    let csv_bytes: i64 = connection
+        .query_row(
+            "SELECT sum(size) FROM records WHERE extension = '.csv'",
+            [],
+            |row| row.get(0),
+        )
+        .expect("query should succeed");
+    assert_eq!(csv_bytes, 5);
+    let indexes: i64 = connection
+        .query_row(
+            "SELECT count(*) FROM sqlite_master WHERE type = 'index' AND name LIKE 'records_%'",
+            [],
+            |row| row.get(0),
+        )
+        .expect("query should succeed");
+    assert_eq!(indexes, 4);
+}
+
+#[cfg(feature = "sqlite")]
+#[test]
+fn sqlite_export_of_a_non_manifest_refuses_and_leaves_no_database() {
+    let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+    let manifest = temp_dir.path().join("bad.jsonl");
+    fs::write(&manifest, "not json\n").expect("file should be written");
+    let out = temp_dir.path().join("bad.db");
+
+    let output = support::vacuum_command("export-bad")
+        .arg("export")
+        .arg(&manifest)
+        .arg("--sqlite")
+        .arg(&out)
+        .output()
+        .expect("vacuum binary should run");
+
+    assert_eq!(output.status.code(), Some(2));
+    let refusal = serde_json::from_slice::<serde_json::Value>(&output.stdout)
+        .expect("refusal should be json");
+    assert_eq!(refusal["refusal"]["code"], "E_BAD_MANIFEST");
+    assert!(!out.exists());
+}
+
+#[cfg(not(feature = "sqlite"))]
+#[test]
+fn export_refuses_without_the_sqlite_feature() {
+    let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+    let manifest = temp_dir.path().join("manifest.jsonl");
+    fs::write(&manifest, "").expect("manifest should be written");
+    let out = temp_dir.path().join("manifest.db");
+
+    let output = support::vacuum_command("export-no-sqlite")
+        .arg("export")
+        .arg(&manifest)
+        .arg("--sqlite")
+        .arg(&out)
+        .output()
+        .expect("vacuum binary should run");
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(output.stdout.is_empty());
+    let stderr = String::from_utf8(output.stderr).expect("stderr should be utf-8");
+    assert!(stderr.contains("--features sqlite"), "stderr: {stderr}");
+    assert!(!out.exists());
+}
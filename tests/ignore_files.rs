@@ -0,0 +1,74 @@
+use std::{fs, path::Path};
+
+mod support;
+
+fn scan(root: &Path, extra_args: &[&str]) -> Vec<String> {
+    let output = support::vacuum_command("ignore-files")
+        .arg(root)
+        .arg("--no-witness")
+        .args(extra_args)
+        .output()
+        .expect("vacuum binary should run");
+    assert!(output.status.success(), "scan should exit 0");
+    String::from_utf8(output.stdout)
+        .expect("stdout should be utf-8")
+        .lines()
+        .map(|line| {
+            let record: serde_json::Value =
+                serde_json::from_str(line).expect("line should be valid json");
+            record["relative_path"]
+                .as_str()
+                .expect("relative_path should be a string")
+                .to_owned()
+        })
+        .collect()
+}
+
+fn project(root: &Path) {
+    fs::create_dir_all(root.join("target/debug")).expect("build dir should be created");
+    fs::create_dir_all(root.join("data/raw")).expect("data dir should be created");
+    fs::write(root.join("target/debug/app"), "bin").expect("file should be written");
+    fs::write(root.join("data/raw/a.csv"), "id\n").expect("file should be written");
+    fs::write(root.join("data/raw/b.tmp"), "x").expect("file should be written");
+    fs::write(root.join("data/raw/keep.tmp"), "x").expect("file should be written");
+    fs::write(root.join(".gitignore"), "/target\n").expect("gitignore should be written");
+    fs::write(root.join("data/.vacuumignore"), "*.tmp\n!raw/keep.tmp\n")
+        .expect("ignore file should be written");
+}
+
+#[test]
+fn vacuumignore_is_honored_and_gitignore_is_opt_in() {
+    let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+    project(temp_dir.path());
+
+    assert_eq!(
+        scan(temp_dir.path(), &[]),
+        vec![
+            ".gitignore",
+            "data/.vacuumignore",
+            "data/raw/a.csv",
+            "data/raw/keep.tmp",
+            "target/debug/app",
+        ]
+    );
+    assert_eq!(
+        scan(temp_dir.path(), &["--gitignore", "--threads", "3"]),
+        vec![
+            ".gitignore",
+            "data/.vacuumignore",
+            "data/raw/a.csv",
+            "data/raw/keep.tmp",
+        ]
+    );
+}
+
+#[test]
+fn no_ignore_disables_every_ignore_file() {
+    let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+    project(temp_dir.path());
+
+    assert_eq!(
+        scan(temp_dir.path(), &["--no-ignore", "--gitignore"]).len(),
+        6
+    );
+}
@@ -0,0 +1,105 @@
+use std::fs;
+
+use serde_json::Value;
+
+mod support;
+
+fn parse_json_lines(stdout: &[u8]) -> Vec<Value> {
+    String::from_utf8(stdout.to_vec())
+        .expect("stdout should be utf-8")
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("line should be valid json"))
+        .collect()
+}
+
+#[test]
+fn mtree_export_imports_back_to_the_same_records() {
+    let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+    let root = temp_dir.path().join("delivery");
+    fs::create_dir_all(root.join("q1")).expect("root should be created");
+    fs::write(root.join("q1/tape 1.csv"), "id\n1\n").expect("file should be written");
+    fs::write(root.join("notes#2.txt"), "hello").expect("file should be written");
+
+    let scan = |label: &str, format: &str| {
+        let output = support::vacuum_command(label)
+            .arg(&root)
+            .args(["--emit-dirs", "--no-follow", "--posix", "--no-witness"])
+            .args(["--format", format])
+            .output()
+            .expect("vacuum binary should run");
+        assert!(output.status.success(), "scan should exit 0");
+        output.stdout
+    };
+    let jsonl = parse_json_lines(&scan("mtree-jsonl", "jsonl"));
+    let spec = scan("mtree-export", "mtree");
+    let spec_text = String::from_utf8(spec.clone()).expect("spec should be utf-8");
+    assert!(spec_text.starts_with("#mtree\n"));
+    assert!(spec_text.contains("./q1/tape\\0401.csv type=file size=5 time="));
+    assert!(spec_text.contains("./notes\\0432.txt type=file"));
+
+    let spec_path = temp_dir.path().join("delivery.mtree");
+    fs::write(&spec_path, spec).expect("spec should be written");
+    let output = support::vacuum_command("mtree-import")
+        .arg("import-mtree")
+        .arg(&spec_path)
+        .arg("--root")
+        .arg(&root)
+        .output()
+        .expect("vacuum binary should run");
+    assert_eq!(output.status.code(), Some(0));
+    let imported = parse_json_lines(&output.stdout);
+
+    assert_eq!(imported.len(), jsonl.len());
+    for (scanned, imported) in jsonl.iter().zip(&imported) {
+        for field in [
+            "path",
+            "relative_path",
+            "root",
+            "size",
+            "mtime",
+            "file_type",
+            "extension",
+            "mime_guess",
+            "mode",
+            "uid",
+            "gid",
+        ] {
+            assert_eq!(scanned[field], imported[field], "{field} should round-trip");
+        }
+    }
+}
+
+#[test]
+fn mtree_export_needs_exactly_one_root() {
+    let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+
+    let output = support::vacuum_command("mtree-two-roots")
+        .arg(temp_dir.path())
+        .arg(temp_dir.path())
+        .args(["--format", "mtree", "--no-witness"])
+        .output()
+        .expect("vacuum binary should run");
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("exactly one ROOT"));
+}
+
+#[test]
+fn malformed_spec_refuses_with_bad_mtree_code() {
+    let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+    let spec_path = temp_dir.path().join("bad.mtree");
+    fs::write(&spec_path, "#mtree\n./a size=big\n").expect("spec should be written");
+
+    let output = support::vacuum_command("mtree-bad")
+        .arg("import-mtree")
+        .arg(&spec_path)
+        .arg("--root")
+        .arg(temp_dir.path())
+        .output()
+        .expect("vacuum binary should run");
+
+    assert_eq!(output.status.code(), Some(2));
+    let refusal = &parse_json_lines(&output.stdout)[0];
+    assert_eq!(refusal["refusal"]["code"], "E_BAD_MTREE");
+    assert_eq!(refusal["refusal"]["detail"]["line"], 2);
+}
@@ -0,0 +1,65 @@
+use std::fs;
+
+use serde_json::{Value, json};
+
+mod support;
+
+#[test]
+fn summarize_rolls_up_a_saved_manifest() {
+    let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+    let root = temp_dir.path().join("delivery");
+    fs::create_dir_all(root.join("tapes")).expect("root should be created");
+    fs::write(root.join("tapes/a.csv"), "id\n1\n").expect("file should be written");
+    fs::write(root.join("tapes/b.csv"), "id\n").expect("file should be written");
+    fs::write(root.join("README.txt"), "hello").expect("file should be written");
+    let manifest = temp_dir.path().join("manifest.jsonl");
+    support::scan_to(&root, &manifest, "summarize-scan", &[]);
+
+    let output = support::vacuum_command("summarize-run")
+        .arg("summarize")
+        .arg(&manifest)
+        .output()
+        .expect("vacuum binary should run");
+
+    assert_eq!(output.status.code(), Some(0));
+    let stdout = String::from_utf8(output.stdout).expect("stdout should be utf-8");
+    assert_eq!(stdout.lines().count(), 1);
+    let summary = serde_json::from_str::<Value>(&stdout).expect("summary should be json");
+    assert_eq!(summary["version"], "vacuum.summary.v0");
+    assert_eq!(summary["records"], 3);
+    assert_eq!(summary["files"], 3);
+    assert_eq!(summary["bytes"], 13);
+    assert_eq!(summary["skipped"], 0);
+    let root_key = summary["roots"]
+        .as_object()
+        .and_then(|roots| roots.keys().next().cloned())
+        .expect("one root");
+    assert!(root_key.ends_with("delivery"));
+    assert_eq!(
+        summary["roots"][&root_key]["top_level"],
+        json!({
+            ".": { "files": 1, "bytes": 5, "skipped": 0 },
+            "tapes": { "files": 2, "bytes": 8, "skipped": 0 },
+        })
+    );
+    assert_eq!(summary["extensions"][".csv"]["files"], 2);
+    assert_eq!(summary["mime"]["text/plain"]["bytes"], 5);
+    assert_eq!(summary["skipped_by_code"], json!({}));
+}
+
+#[test]
+fn summarize_refuses_a_non_manifest() {
+    let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+    let manifest = temp_dir.path().join("not-a-manifest.jsonl");
+    fs::write(&manifest, "{\"hello\":1}\n").expect("file should be written");
+
+    let output = support::vacuum_command("summarize-bad")
+        .arg("summarize")
+        .arg(&manifest)
+        .output()
+        .expect("vacuum binary should run");
+
+    assert_eq!(output.status.code(), Some(2));
+    let refusal = serde_json::from_slice::<Value>(&output.stdout).expect("refusal is json");
+    assert_eq!(refusal["refusal"]["code"], "E_BAD_MANIFEST");
+}
@@ -24,6 +24,19 @@ pub fn vacuum_command(label: &str) -> Command {
     command
 }
 
+/// Scans `root` without a witness record and saves stdout to `manifest`.
+#[allow(dead_code)]
+pub fn scan_to(root: &Path, manifest: &Path, label: &str, extra_args: &[&str]) {
+    let output = vacuum_command(label)
+        .arg(root)
+        .arg("--no-witness")
+        .args(extra_args)
+        .output()
+        .expect("vacuum binary should run");
+    assert!(output.status.success(), "scan should exit 0");
+    fs::write(manifest, output.stdout).expect("manifest should be written");
+}
+
 #[allow(dead_code)]
 pub fn vacuum_binary() -> &'static str {
     WRAPPER_BINARY.get_or_init(create_vacuum_wrapper).as_str()
@@ -0,0 +1,220 @@
+use std::{fs, path::Path};
+
+use serde_json::Value;
+
+mod support;
+
+fn verify(manifest: &Path, home: Option<&Path>) -> (Option<i32>, Vec<Value>) {
+    let mut command = support::vacuum_command("verify-run");
+    if let Some(home) = home {
+        command.env("HOME", home);
+    }
+    let output = command
+        .arg("verify")
+        .arg(manifest)
+        .output()
+        .expect("vacuum binary should run");
+    let events = String::from_utf8(output.stdout)
+        .expect("stdout should be utf-8")
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("line should be valid json"))
+        .collect();
+    (output.status.code(), events)
+}
+
+/// Scans with the witness ledger under `home`, so verify finds the run.
+fn witnessed_scan(home: &Path, roots: &[&Path], manifest: &Path, extra_args: &[&str]) {
+    fs::create_dir_all(home).expect("home should be created");
+    let output = support::vacuum_command("verify-witnessed-scan")
+        .env("HOME", home)
+        .args(roots)
+        .args(extra_args)
+        .output()
+        .expect("vacuum binary should run");
+    assert!(output.status.success(), "scan should exit 0");
+    fs::write(manifest, output.stdout).expect("manifest should be written");
+}
+
+#[test]
+fn untouched_tree_verifies_with_exit_zero() {
+    let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+    let root = temp_dir.path().join("delivery");
+    fs::create_dir_all(root.join("nested")).expect("root should be created");
+    fs::write(root.join("tape.csv"), "id\n1\n").expect("file should be written");
+    fs::write(root.join("nested/model.json"), "{}").expect("file should be written");
+    let manifest = temp_dir.path().join("manifest.jsonl");
+    support::scan_to(&root, &manifest, "verify-scan", &[]);
+
+    let (code, events) = verify(&manifest, None);
+
+    assert_eq!(code, Some(0));
+    assert!(events.is_empty(), "unexpected findings: {events:?}");
+}
+
+#[test]
+fn truncated_vanished_and_new_files_are_reported_with_exit_one() {
+    let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+    let root = temp_dir.path().join("delivery");
+    fs::create_dir_all(&root).expect("root should be created");
+    fs::write(root.join("tape.csv"), "id\n1\n2\n").expect("file should be written");
+    fs::write(root.join("moved.csv"), "id\n").expect("file should be written");
+    let manifest = temp_dir.path().join("manifest.jsonl");
+    support::scan_to(&root, &manifest, "verify-scan", &[]);
+
+    fs::write(root.join("tape.csv"), "id\n").expect("file should be truncated");
+    fs::remove_file(root.join("moved.csv")).expect("file should be removed");
+    fs::write(root.join("late.csv"), "id\n").expect("file should be written");
+
+    let (code, events) = verify(&manifest, None);
+
+    assert_eq!(code, Some(1));
+    let findings = events
+        .iter()
+        .filter(|event| event["event"] != "mtime_changed")
+        .map(|event| {
+            (
+                event["relative_path"].as_str().unwrap_or_default(),
+                event["event"].as_str().unwrap_or_default(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        findings,
+        vec![
+            ("late.csv", "added"),
+            ("moved.csv", "removed"),
+            ("tape.csv", "size_changed"),
+        ]
+    );
+}
+
+#[test]
+fn original_filters_keep_excluded_files_out_of_findings() {
+    let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+    let home = temp_dir.path().join("home");
+    let root = temp_dir.path().join("delivery");
+    fs::create_dir_all(&root).expect("root should be created");
+    fs::write(root.join("tape.csv"), "id\n").expect("file should be written");
+    fs::write(root.join("scratch.tmp"), "x").expect("file should be written");
+    let manifest = temp_dir.path().join("manifest.jsonl");
+    witnessed_scan(&home, &[&root], &manifest, &["--exclude", "*.tmp"]);
+
+    let (unwitnessed_code, _) = verify(&manifest, None);
+    let (witnessed_code, events) = verify(&manifest, Some(&home));
+
+    assert_eq!(unwitnessed_code, Some(1));
+    assert_eq!(witnessed_code, Some(0));
+    assert!(events.is_empty(), "unexpected findings: {events:?}");
+}
+
+#[test]
+fn files_from_runs_restat_only_the_listed_paths() {
+    let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+    let home = temp_dir.path().join("home");
+    let root = temp_dir.path().join("delivery");
+    fs::create_dir_all(&root).expect("root should be created");
+    fs::write(root.join("tape.csv"), "id\n").expect("file should be written");
+    fs::write(root.join("unlisted.csv"), "id\n").expect("file should be written");
+    let list = temp_dir.path().join("files.txt");
+    fs::write(&list, "tape.csv\n").expect("list should be written");
+    let manifest = temp_dir.path().join("manifest.jsonl");
+    witnessed_scan(
+        &home,
+        &[&root],
+        &manifest,
+        &["--files-from", list.to_str().expect("utf-8 path")],
+    );
+
+    let (code, events) = verify(&manifest, Some(&home));
+
+    assert_eq!(code, Some(0));
+    assert!(events.is_empty(), "unexpected findings: {events:?}");
+}
+
+#[test]
+fn witnessed_roots_without_records_are_rescanned() {
+    let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+    let home = temp_dir.path().join("home");
+    let delivery = temp_dir.path().join("delivery");
+    let inbox = temp_dir.path().join("inbox");
+    fs::create_dir_all(&delivery).expect("root should be created");
+    fs::create_dir_all(&inbox).expect("empty root should be created");
+    fs::write(delivery.join("tape.csv"), "id\n").expect("file should be written");
+    let manifest = temp_dir.path().join("manifest.jsonl");
+    witnessed_scan(&home, &[&delivery, &inbox], &manifest, &[]);
+
+    fs::write(inbox.join("late.csv"), "id\n").expect("file should be written");
+    let (code, events) = verify(&manifest, Some(&home));
+
+    assert_eq!(code, Some(1));
+    assert_eq!(events.len(), 1, "unexpected findings: {events:?}");
+    assert_eq!(events[0]["event"], "added");
+    assert_eq!(events[0]["relative_path"], "late.csv");
+}
+
+#[test]
+fn recorded_paths_hidden_by_filters_are_restated_not_removed() {
+    let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+    let home = temp_dir.path().join("home");
+    let root = temp_dir.path().join("delivery");
+    fs::create_dir_all(&root).expect("root should be created");
+    fs::write(root.join("tape.csv"), "id\n").expect("file should be written");
+    let manifest = temp_dir.path().join("manifest.jsonl");
+    witnessed_scan(&home, &[&root], &manifest, &["--max-size", "10"]);
+
+    fs::write(root.join("tape.csv"), "id\n1\n2\n3\n4\n5\n").expect("file should grow");
+
+    let (code, events) = verify(&manifest, Some(&home));
+
+    assert_eq!(code, Some(1));
+    let findings = events
+        .iter()
+        .filter(|event| event["event"] != "mtime_changed")
+        .map(|event| event["event"].as_str().unwrap_or_default())
+        .collect::<Vec<_>>();
+    assert_eq!(findings, vec!["size_changed"]);
+}
+
+#[cfg(unix)]
+#[test]
+fn followed_links_are_restated_by_their_entry_path() {
+    let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+    let home = temp_dir.path().join("home");
+    let root = temp_dir.path().join("delivery");
+    let outside = temp_dir.path().join("outside");
+    fs::create_dir_all(&root).expect("root should be created");
+    fs::create_dir_all(&outside).expect("outside should be created");
+    fs::write(outside.join("tape.csv"), "id\n").expect("file should be written");
+    std::os::unix::fs::symlink(outside.join("tape.csv"), root.join("link.csv"))
+        .expect("symlink should be created");
+    let manifest = temp_dir.path().join("manifest.jsonl");
+    witnessed_scan(&home, &[&root], &manifest, &["--max-size", "10"]);
+
+    fs::write(outside.join("tape.csv"), "id\n1\n2\n3\n4\n5\n").expect("file should grow");
+
+    let (code, events) = verify(&manifest, Some(&home));
+
+    assert_eq!(code, Some(1));
+    let findings = events
+        .iter()
+        .filter(|event| event["event"] != "mtime_changed")
+        .map(|event| {
+            (
+                event["relative_path"].as_str().unwrap_or_default(),
+                event["event"].as_str().unwrap_or_default(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(findings, vec![("link.csv", "size_changed")]);
+}
+
+#[test]
+fn missing_manifest_refuses_with_io_code() {
+    let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+
+    let (code, events) = verify(&temp_dir.path().join("missing.jsonl"), None);
+
+    assert_eq!(code, Some(2));
+    assert_eq!(events[0]["outcome"], "REFUSAL");
+    assert_eq!(events[0]["refusal"]["code"], "E_IO");
+}
@@ -67,6 +67,111 @@ fn successful_scan_appends_witness_record_by_default() {
     );
 }
 
+#[test]
+fn roots_from_list_is_recorded_as_hashed_witness_input() {
+    let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+    let witness_path = temp_dir.path().join("witness.jsonl");
+    let list = temp_dir.path().join("roots.txt");
+    let contents = format!("{}\n", fixture("simple").display());
+    fs::write(&list, &contents).expect("root list should be written");
+
+    let output = support::vacuum_command("witness-roots-from")
+        .arg("--roots-from")
+        .arg(&list)
+        .env("EPISTEMIC_WITNESS", &witness_path)
+        .output()
+        .expect("vacuum binary should run");
+
+    assert!(output.status.success(), "scan should exit 0");
+    let record = &read_witness_lines(&witness_path)[0];
+    let inputs = record["inputs"]
+        .as_array()
+        .expect("inputs should be an array");
+    assert_eq!(inputs.len(), 2);
+    assert_eq!(
+        inputs[0]["path"],
+        fixture("simple").to_string_lossy().as_ref()
+    );
+    assert_eq!(inputs[1]["path"], list.to_string_lossy().as_ref());
+    assert_eq!(
+        inputs[1]["hash"],
+        format!("blake3:{}", blake3::hash(contents.as_bytes()).to_hex())
+    );
+    assert_eq!(inputs[1]["bytes"], contents.len());
+    assert_eq!(
+        record["params"]["roots_from"],
+        list.to_string_lossy().as_ref()
+    );
+}
+
+#[test]
+fn metadata_predicates_are_recorded_resolved_in_witness_params() {
+    let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+    let witness_path = temp_dir.path().join("witness.jsonl");
+
+    let output = support::vacuum_command("witness-predicates")
+        .arg(fixture("simple"))
+        .args(["--min-size", "1KB", "--newer-than", "2026-01-31"])
+        .env("EPISTEMIC_WITNESS", &witness_path)
+        .output()
+        .expect("vacuum binary should run");
+
+    assert!(output.status.success(), "scan should exit 0");
+    let params = &read_witness_lines(&witness_path)[0]["params"];
+    assert_eq!(params["min_size"], 1000);
+    assert!(params["max_size"].is_null());
+    assert_eq!(params["newer_than"], "2026-01-31T00:00:00.000Z");
+    assert!(params["older_than"].is_null());
+}
+
+#[test]
+fn ignore_files_read_by_the_walk_are_recorded_in_witness_params() {
+    let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+    let witness_path = temp_dir.path().join("witness.jsonl");
+    let root = temp_dir.path().join("delivery");
+    fs::create_dir_all(root.join("nested")).expect("root should be created");
+    fs::write(root.join(".vacuumignore"), "*.tmp\n").expect("ignore file should be written");
+    fs::write(root.join("nested/.gitignore"), "*.csv\n").expect("ignore file should be written");
+    fs::write(root.join("nested/tape.csv"), "id\n").expect("file should be written");
+
+    let output = support::vacuum_command("witness-ignore-files")
+        .arg(&root)
+        .env("EPISTEMIC_WITNESS", &witness_path)
+        .output()
+        .expect("vacuum binary should run");
+
+    assert!(output.status.success(), "scan should exit 0");
+    let params = &read_witness_lines(&witness_path)[0]["params"];
+    assert_eq!(
+        params["ignore_files"],
+        serde_json::json!([{
+            "path": root.join(".vacuumignore").to_string_lossy(),
+            "hash": format!("blake3:{}", blake3::hash(b"*.tmp\n").to_hex()),
+        }])
+    );
+}
+
+#[test]
+fn csv_output_hash_covers_the_emitted_bytes() {
+    let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+    let witness_path = temp_dir.path().join("witness.jsonl");
+
+    let output = support::vacuum_command("witness-csv")
+        .arg(fixture("simple"))
+        .args(["--format", "csv"])
+        .env("EPISTEMIC_WITNESS", &witness_path)
+        .output()
+        .expect("vacuum binary should run");
+
+    assert!(output.status.success(), "scan should exit 0");
+    let record = &read_witness_lines(&witness_path)[0];
+    assert_eq!(record["params"]["format"], "csv");
+    assert_eq!(
+        record["output_hash"],
+        format!("blake3:{}", blake3::hash(&output.stdout).to_hex())
+    );
+}
+
 #[test]
 fn successful_scan_appends_to_cmdrvl_witness_by_default() {
     let temp_dir = tempfile::tempdir().expect("tempdir should be created");
@@ -159,6 +264,27 @@ fn refusal_run_appends_refusal_witness_record() {
     );
 }
 
+#[test]
+fn guardrail_refusal_appends_refusal_witness_record() {
+    let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+    let witness_path = temp_dir.path().join("witness.jsonl");
+
+    let output = support::vacuum_command("witness-guardrail")
+        .arg(fixture("large_file_counts"))
+        .args(["--max-files", "1"])
+        .env("EPISTEMIC_WITNESS", &witness_path)
+        .output()
+        .expect("vacuum binary should run");
+
+    assert_eq!(output.status.code(), Some(2), "guardrail should exit 2");
+    let lines = read_witness_lines(&witness_path);
+    assert_eq!(lines.len(), 1);
+    assert_eq!(lines[0]["outcome"], "REFUSAL");
+    assert_eq!(lines[0]["exit_code"], 2);
+    assert_eq!(lines[0]["params"]["max_files"], 1);
+    assert!(lines[0]["params"]["max_depth"].is_null());
+}
+
 #[test]
 fn consecutive_runs_chain_prev_ids() {
     let temp_dir = tempfile::tempdir().expect("tempdir should be created");
@@ -180,6 +306,34 @@ fn consecutive_runs_chain_prev_ids() {
     assert_eq!(lines[1]["prev"], lines[0]["id"]);
 }
 
+#[test]
+fn concurrent_runs_extend_a_single_linear_chain() {
+    let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+    let witness_path = temp_dir.path().join("witness.jsonl");
+
+    let children = (0..10)
+        .map(|_| {
+            support::vacuum_command("witness-concurrent")
+                .arg(fixture("simple"))
+                .env("EPISTEMIC_WITNESS", &witness_path)
+                .stdout(std::process::Stdio::null())
+                .spawn()
+                .expect("vacuum binary should spawn")
+        })
+        .collect::<Vec<_>>();
+    for mut child in children {
+        let status = child.wait().expect("vacuum run should finish");
+        assert!(status.success(), "scan should exit 0");
+    }
+
+    let lines = read_witness_lines(&witness_path);
+    assert_eq!(lines.len(), 10);
+    assert!(lines[0]["prev"].is_null());
+    for pair in lines.windows(2) {
+        assert_eq!(pair[1]["prev"], pair[0]["id"], "chain must not fork");
+    }
+}
+
 #[test]
 fn no_witness_flag_suppresses_witness_append() {
     let temp_dir = tempfile::tempdir().expect("tempdir should be created");
@@ -0,0 +1,86 @@
+use std::{fs, path::PathBuf};
+
+mod support;
+
+fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join(name)
+}
+
+fn record_scans(witness_path: &PathBuf, count: usize) {
+    for _ in 0..count {
+        let output = support::vacuum_command("witness-verify-scan")
+            .arg(fixture("simple"))
+            .env("EPISTEMIC_WITNESS", witness_path)
+            .output()
+            .expect("vacuum binary should run");
+        assert!(output.status.success(), "scan should exit 0");
+    }
+}
+
+fn verify(witness_path: &PathBuf) -> (Option<i32>, serde_json::Value) {
+    let output = support::vacuum_command("witness-verify")
+        .args(["witness", "verify", "--json"])
+        .env("EPISTEMIC_WITNESS", witness_path)
+        .output()
+        .expect("vacuum binary should run");
+    let report = serde_json::from_slice(&output.stdout).expect("verdict should be valid json");
+    (output.status.code(), report)
+}
+
+#[test]
+fn ledger_written_by_scans_verifies_intact() {
+    let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+    let witness_path = temp_dir.path().join("witness.jsonl");
+    record_scans(&witness_path, 3);
+
+    let (code, report) = verify(&witness_path);
+
+    assert_eq!(code, Some(0));
+    assert_eq!(report["verdict"], "INTACT");
+    assert_eq!(report["records"], 3);
+    assert_eq!(report["findings"], serde_json::json!([]));
+}
+
+#[test]
+fn edited_and_truncated_lines_break_the_chain() {
+    let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+    let witness_path = temp_dir.path().join("witness.jsonl");
+    record_scans(&witness_path, 3);
+
+    let contents = fs::read_to_string(&witness_path).expect("ledger should be readable");
+    let mut lines = contents.lines().map(str::to_owned).collect::<Vec<_>>();
+    lines[1] = lines[1].replace("SCAN_COMPLETE", "REFUSAL");
+    lines.push("{\"id\":\"blake3:tru".to_owned());
+    fs::write(&witness_path, lines.join("\n") + "\n").expect("ledger should be rewritten");
+
+    let (code, report) = verify(&witness_path);
+
+    assert_eq!(code, Some(1));
+    assert_eq!(report["verdict"], "BROKEN");
+    let kinds = report["findings"]
+        .as_array()
+        .expect("findings should be an array")
+        .iter()
+        .map(|finding| (finding["line"].clone(), finding["kind"].clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        kinds,
+        vec![
+            (serde_json::json!(2), serde_json::json!("id_mismatch")),
+            (serde_json::json!(4), serde_json::json!("unparseable")),
+        ]
+    );
+}
+
+#[test]
+fn missing_ledger_is_trivially_intact() {
+    let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+
+    let (code, report) = verify(&temp_dir.path().join("witness.jsonl"));
+
+    assert_eq!(code, Some(0));
+    assert_eq!(report["records"], 0);
+}